@@ -0,0 +1,48 @@
+use std::fmt;
+
+/// Puzzle answer, every day so far answers with an integer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(v) => write!(f, "{v}"),
+            Answer::Unsigned(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(v: i32) -> Self {
+        Answer::Signed(v.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(v: i64) -> Self {
+        Answer::Signed(v)
+    }
+}
+
+impl From<u16> for Answer {
+    fn from(v: u16) -> Self {
+        Answer::Unsigned(v.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(v: u64) -> Self {
+        Answer::Unsigned(v)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(v: usize) -> Self {
+        Answer::Unsigned(v as u64)
+    }
+}
@@ -0,0 +1,259 @@
+// unused when no days are enabled
+#[allow(unused_imports)]
+use common::Config;
+
+#[allow(unused_imports)]
+use crate::{Answer, Part};
+
+/// Answers to `parts` in the same order, every day parses its input once
+pub(crate) type Answers = Result<Vec<Answer>, anyhow::Error>;
+
+#[cfg(feature = "day_01")]
+pub(crate) fn day_01(parts: &[Part], input: &str, config: &Config) -> Answers {
+    use anyhow::Context;
+
+    let day_01::Problem::<i64> { mut entries } = input.parse()?;
+    let entries = day_01::Sorted::sort(&mut entries);
+
+    let target = config.param_or(1, "target", day_01::TARGET_SUM)?;
+
+    parts
+        .iter()
+        .map(|part| {
+            let terms = match part {
+                Part::One => day_01::find_two_sum(entries, target, day_01::Reuse::Distinct)
+                    .map(|(a, b)| vec![a, b]),
+                Part::Two => day_01::find_three_sum(entries, target, day_01::Reuse::Distinct)
+                    .map(|(a, b, c)| vec![a, b, c]),
+            };
+            let product = common::checked_product(terms.context("no entries summing to target")?)?;
+
+            Ok(product.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_02")]
+pub(crate) fn day_02(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let day_02::Problem { passwords } = input.parse()?;
+
+    Ok(parts
+        .iter()
+        .map(|part| {
+            let count = match part {
+                Part::One => day_02::count_valid_passwords(&passwords, &day_02::CountPolicy),
+                Part::Two => {
+                    day_02::count_valid_passwords(&passwords, &day_02::PositionPolicy::default())
+                }
+            };
+
+            count.into()
+        })
+        .collect())
+}
+
+#[cfg(feature = "day_03")]
+pub(crate) fn day_03(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let p: day_03::Problem = input.parse()?;
+
+    parts
+        .iter()
+        .map(|part| {
+            let count = match part {
+                Part::One => day_03::count_collisions(&p, 3, 1),
+                Part::Two => day_03::product_of_collisions(&p, &day_03::PART_TWO_SLOPES)?,
+            };
+
+            Ok(count.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_04")]
+pub(crate) fn day_04(parts: &[Part], input: &str, config: &Config) -> Answers {
+    let mode = config.param_or(4, "mode", day_04::ParseMode::default())?;
+    let p = day_04::parse_with_mode(input, mode)?.problem;
+    let policy = match config.param::<std::path::PathBuf>(4, "policy")? {
+        Some(path) => day_04::Policy::load(&path)?,
+        None => day_04::Policy::standard().clone(),
+    };
+
+    Ok(parts
+        .iter()
+        .map(|part| {
+            let count = match part {
+                Part::One => policy.presence_only().count_valid(&p),
+                Part::Two => policy.count_valid(&p),
+            };
+
+            count.into()
+        })
+        .collect())
+}
+
+#[cfg(feature = "day_05")]
+pub(crate) fn day_05(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let day_05::Problem { boarding_passes } = input.parse()?;
+    let seat_ids: Vec<_> = boarding_passes
+        .iter()
+        .map(|s| day_05::str_to_seat(s))
+        .collect();
+
+    parts
+        .iter()
+        .map(|part| {
+            let seat_id = match part {
+                Part::One => *seat_ids
+                    .iter()
+                    .max()
+                    .ok_or_else(|| anyhow::anyhow!("couldn't find max seat id (empty list?)"))?,
+                Part::Two => day_05::find_my_seat_id(&seat_ids)?,
+            };
+
+            Ok(seat_id.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_06")]
+pub(crate) fn day_06(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let p: day_06::Problem = input.parse()?;
+
+    Ok(parts
+        .iter()
+        .map(|part| {
+            let sum = match part {
+                Part::One => day_06::sum_unique_answers(&p),
+                Part::Two => day_06::sum_shared_answers(&p),
+            };
+
+            sum.into()
+        })
+        .collect())
+}
+
+#[cfg(feature = "day_07")]
+pub(crate) fn day_07(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let p: day_07::Problem = input.try_into()?;
+
+    parts
+        .iter()
+        .map(|part| {
+            let count = match part {
+                Part::One => day_07::count_containing_bags(&p, "shiny gold")?,
+                Part::Two => day_07::count_contained_bags(&p, "shiny gold")?,
+            };
+
+            Ok(count.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_08")]
+pub(crate) fn day_08(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let p: day_08::Problem = input.parse()?;
+
+    parts
+        .iter()
+        .map(|part| {
+            let accumulator_value = match part {
+                Part::One => day_08::run_until_first_loop(&p)?.0,
+                Part::Two => day_08::brute_force(&p)?,
+            };
+
+            Ok(accumulator_value.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_09")]
+pub(crate) fn day_09(parts: &[Part], input: &str, config: &Config) -> Answers {
+    use anyhow::Context;
+
+    let day_09::Problem::<i64> { numbers } = input.parse()?;
+
+    let preamble_length = config.param_or(9, "preamble_length", day_09::PREAMBLE_LENGTH)?;
+    let value = day_09::find_first_not_following_the_rule(preamble_length, &numbers)?;
+
+    parts
+        .iter()
+        .map(|part| {
+            let answer = match part {
+                Part::One => value,
+                Part::Two => {
+                    let v = day_09::find_continuous_set_summing_to_value(value, &numbers)?;
+                    let min = v.iter().min().context("empty continuous set")?;
+                    let max = v.iter().max().context("empty continuous set")?;
+
+                    common::Integer::try_add(*min, *max)?
+                }
+            };
+
+            Ok(answer.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_10")]
+pub(crate) fn day_10(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let p: day_10::Problem = input.parse()?;
+
+    Ok(parts
+        .iter()
+        .map(|part| match part {
+            Part::One => {
+                let (one_diff, three_diff) = day_10::solve_part_1(&p);
+                (one_diff * three_diff).into()
+            }
+            Part::Two => day_10::solve_part_2(&p).into(),
+        })
+        .collect())
+}
+
+#[cfg(feature = "day_11")]
+pub(crate) fn day_11(parts: &[Part], input: &str, config: &Config) -> Answers {
+    let p: day_11::Problem = input.parse()?;
+
+    parts
+        .iter()
+        .map(|part| {
+            let occupied_seats = match part {
+                Part::One => {
+                    let limit = config.param_or(
+                        11,
+                        "occupied_seats_limit",
+                        day_11::OCCUPIED_SEATS_LIMIT,
+                    )?;
+                    day_11::solve_part_1_with_limit(p.clone(), limit)?
+                }
+                Part::Two => {
+                    let limit = config.param_or(
+                        11,
+                        "visible_occupied_seats_limit",
+                        day_11::VISIBLE_OCCUPIED_SEATS_LIMIT,
+                    )?;
+                    day_11::solve_part_2_with_limit(p.clone(), limit)?
+                }
+            };
+
+            Ok(occupied_seats.into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "day_12")]
+pub(crate) fn day_12(parts: &[Part], input: &str, _config: &Config) -> Answers {
+    let p: day_12::Problem = input.parse()?;
+
+    Ok(parts
+        .iter()
+        .map(|part| {
+            let manhattan_distance = match part {
+                Part::One => day_12::solve_part_1(&p),
+                Part::Two => day_12::solve_part_2(&p),
+            };
+
+            manhattan_distance.into()
+        })
+        .collect())
+}
@@ -0,0 +1,211 @@
+//! Every day's solvers behind one API
+//!
+//! Days are re-exported as modules (`aoc::day_01`, `aoc::day_02`, ...) and
+//! can be enabled one by one with cargo features of the same names, all of
+//! them are enabled by default. `serde` feature enables serialization of
+//! answers and of every enabled day's parsed inputs.
+
+use anyhow::bail;
+pub use common::Config;
+
+mod answer;
+mod days;
+
+pub use answer::Answer;
+
+#[cfg(feature = "day_01")]
+pub use day_01;
+#[cfg(feature = "day_02")]
+pub use day_02;
+#[cfg(feature = "day_03")]
+pub use day_03;
+#[cfg(feature = "day_04")]
+pub use day_04;
+#[cfg(feature = "day_05")]
+pub use day_05;
+#[cfg(feature = "day_06")]
+pub use day_06;
+#[cfg(feature = "day_07")]
+pub use day_07;
+#[cfg(feature = "day_08")]
+pub use day_08;
+#[cfg(feature = "day_09")]
+pub use day_09;
+#[cfg(feature = "day_10")]
+pub use day_10;
+#[cfg(feature = "day_11")]
+pub use day_11;
+#[cfg(feature = "day_12")]
+pub use day_12;
+
+/// Days enabled in this build
+pub const DAYS: &[u8] = &[
+    #[cfg(feature = "day_01")]
+    1,
+    #[cfg(feature = "day_02")]
+    2,
+    #[cfg(feature = "day_03")]
+    3,
+    #[cfg(feature = "day_04")]
+    4,
+    #[cfg(feature = "day_05")]
+    5,
+    #[cfg(feature = "day_06")]
+    6,
+    #[cfg(feature = "day_07")]
+    7,
+    #[cfg(feature = "day_08")]
+    8,
+    #[cfg(feature = "day_09")]
+    9,
+    #[cfg(feature = "day_10")]
+    10,
+    #[cfg(feature = "day_11")]
+    11,
+    #[cfg(feature = "day_12")]
+    12,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl TryFrom<u8> for Part {
+    type Error = anyhow::Error;
+
+    fn try_from(part: u8) -> Result<Self, Self::Error> {
+        match part {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => bail!("there is no part {}", part),
+        }
+    }
+}
+
+/// Solves `part` of `day` with default puzzle parameters
+///
+/// # Errors
+///
+/// Returns error if the day is not enabled, the part does not exist, the
+/// input is malformed or has no solution
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, anyhow::Error> {
+    solve_with_config(day, part, input, &Config::default())
+}
+
+/// Solves `part` of `day` with puzzle parameters overridden by `config`
+///
+/// # Errors
+///
+/// See `solve`
+pub fn solve_with_config(
+    day: u8,
+    part: u8,
+    input: &str,
+    config: &Config,
+) -> Result<Answer, anyhow::Error> {
+    let part = Part::try_from(part)?;
+    let mut answers = solve_parts(day, &[part], input, config)?;
+
+    Ok(answers.remove(0))
+}
+
+/// Solves both parts of `day`, parsing its input only once
+///
+/// # Errors
+///
+/// See `solve`
+pub fn solve_both(day: u8, input: &str, config: &Config) -> Result<[Answer; 2], anyhow::Error> {
+    let answers = solve_parts(day, &[Part::One, Part::Two], input, config)?;
+
+    Ok([answers[0], answers[1]])
+}
+
+// arguments are unused when no days are enabled
+#[allow(unused_variables)]
+fn solve_parts(day: u8, parts: &[Part], input: &str, config: &Config) -> days::Answers {
+    match day {
+        #[cfg(feature = "day_01")]
+        1 => days::day_01(parts, input, config),
+        #[cfg(feature = "day_02")]
+        2 => days::day_02(parts, input, config),
+        #[cfg(feature = "day_03")]
+        3 => days::day_03(parts, input, config),
+        #[cfg(feature = "day_04")]
+        4 => days::day_04(parts, input, config),
+        #[cfg(feature = "day_05")]
+        5 => days::day_05(parts, input, config),
+        #[cfg(feature = "day_06")]
+        6 => days::day_06(parts, input, config),
+        #[cfg(feature = "day_07")]
+        7 => days::day_07(parts, input, config),
+        #[cfg(feature = "day_08")]
+        8 => days::day_08(parts, input, config),
+        #[cfg(feature = "day_09")]
+        9 => days::day_09(parts, input, config),
+        #[cfg(feature = "day_10")]
+        10 => days::day_10(parts, input, config),
+        #[cfg(feature = "day_11")]
+        11 => days::day_11(parts, input, config),
+        #[cfg(feature = "day_12")]
+        12 => days::day_12(parts, input, config),
+        _ => bail!("day {} is not implemented or not enabled", day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "day_01")]
+    #[test]
+    fn test_solve() {
+        let input = "1721\n979\n366\n299\n675\n1456";
+
+        assert_eq!(solve(1, 1, input).unwrap(), Answer::Signed(514_579));
+        assert_eq!(solve(1, 2, input).unwrap(), Answer::Signed(241_861_950));
+    }
+
+    #[cfg(feature = "day_12")]
+    #[test]
+    fn test_solve_display() {
+        assert_eq!(
+            solve(12, 1, "F10\nN3\nF7\nR90\nF11").unwrap().to_string(),
+            "25"
+        );
+    }
+
+    #[cfg(feature = "day_01")]
+    #[test]
+    fn test_solve_with_config() {
+        let input = "1\n2\n3";
+        let (config, _) =
+            Config::from_args(["--set", "day_01.target=5"].map(String::from)).unwrap();
+
+        assert!(solve(1, 1, input).is_err());
+        assert_eq!(
+            solve_with_config(1, 1, input, &config).unwrap(),
+            Answer::Signed(6)
+        );
+    }
+
+    #[cfg(feature = "day_01")]
+    #[test]
+    fn test_solve_both() {
+        let input = "1721\n979\n366\n299\n675\n1456";
+
+        assert_eq!(
+            solve_both(1, input, &Config::default()).unwrap(),
+            [Answer::Signed(514_579), Answer::Signed(241_861_950)]
+        );
+        assert!(solve_both(1, "x", &Config::default()).is_err());
+    }
+
+    #[test]
+    fn test_solve_unknown_day_or_part() {
+        assert!(solve(25, 1, "").is_err());
+        assert!(solve(1, 3, "1").is_err());
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context};
+
+pub const DEFAULT_YEAR: u16 = 2020;
+pub const WORKSPACE_CONFIG_FILE: &str = "aoc.toml";
+const USER_CONFIG_FILE: &str = "advent-of-code-2020/config.toml";
+const ENV_PREFIX: &str = "AOC_";
+
+/// Settings shared by the runner and every day's binary
+///
+/// Configuration is layered, each layer overriding values set by previous
+/// ones:
+///
+/// 1. user config file (`$XDG_CONFIG_HOME/advent-of-code-2020/config.toml`)
+/// 2. workspace config file (`aoc.toml` in current or any parent directory)
+/// 3. environment variables (`AOC_YEAR`, `AOC_INPUT_DIR`, `AOC_CACHE_DIR`,
+///    `AOC_SESSION` and `AOC_DAY_XX_<PARAM>` for per-day parameters)
+/// 4. command line flags (`--year`, `--input-dir`, `--cache-dir`,
+///    `--session` and `--set day_XX.<param>=<value>`)
+///
+/// Config files look like this:
+///
+/// ```toml
+/// year = 2020
+/// input_dir = "inputs"
+///
+/// [day_09]
+/// preamble_length = 25
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    pub year: Option<u16>,
+    pub input_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub session: Option<String>,
+    days: BTreeMap<u8, BTreeMap<String, String>>,
+}
+
+impl FromStr for Config {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: toml::Table = s.parse()?;
+        let mut config = Config::default();
+
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("year", toml::Value::Integer(year)) => config.year = Some(u16::try_from(year)?),
+                ("input_dir", toml::Value::String(path)) => config.input_dir = Some(path.into()),
+                ("cache_dir", toml::Value::String(path)) => config.cache_dir = Some(path.into()),
+                ("session", toml::Value::String(session)) => config.session = Some(session),
+                (key, toml::Value::Table(params)) => {
+                    let day = parse_day_key(key)?;
+
+                    for (name, value) in params {
+                        let value = match value {
+                            toml::Value::String(s) => s,
+                            toml::Value::Integer(_)
+                            | toml::Value::Float(_)
+                            | toml::Value::Boolean(_) => value.to_string(),
+                            _ => bail!("unsupported value of {}.{}", key, name),
+                        };
+
+                        config.set_param(day, &name, value);
+                    }
+                }
+                (key, _) => bail!("unknown or malformed key {}", key),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Parses `day_XX` into day number
+fn parse_day_key(key: &str) -> Result<u8, anyhow::Error> {
+    key.strip_prefix("day_")
+        .and_then(|n| n.parse().ok())
+        .filter(|n| (1..=25).contains(n))
+        .ok_or_else(|| anyhow!("expected day_XX, got {}", key))
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, anyhow::Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|dir| dir.join(USER_CONFIG_FILE))
+}
+
+fn workspace_config_path() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+
+    cwd.ancestors()
+        .map(|dir| dir.join(WORKSPACE_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+impl Config {
+    /// Loads every configuration layer, `args` should not contain program
+    /// name. Returns config and positional arguments left after taking out
+    /// the flags.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if any of the layers is malformed
+    pub fn load<I>(args: I) -> Result<(Config, Vec<String>), anyhow::Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut config = Config::default();
+
+        for path in [user_config_path(), workspace_config_path()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(content) = read_optional(&path)? {
+                let layer = content
+                    .parse()
+                    .with_context(|| format!("malformed config file {}", path.display()))?;
+                config.merge(layer);
+            }
+        }
+
+        config.merge(Config::from_env_vars(env::vars())?);
+
+        let (layer, positional) = Config::from_args(args)?;
+        config.merge(layer);
+
+        Ok((config, positional))
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if any `AOC_` prefixed variable is malformed
+    pub fn from_env_vars<I>(vars: I) -> Result<Config, anyhow::Error>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut config = Config::default();
+
+        for (key, value) in vars {
+            let Some(key) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+
+            match key {
+                "YEAR" => config.year = Some(value.parse()?),
+                "INPUT_DIR" => config.input_dir = Some(value.into()),
+                "CACHE_DIR" => config.cache_dir = Some(value.into()),
+                "SESSION" => config.session = Some(value),
+                key => {
+                    // `DAY_09_PREAMBLE_LENGTH` -> (9, "preamble_length")
+                    let Some((day, name)) = key
+                        .strip_prefix("DAY_")
+                        .and_then(|k| k.split_once('_'))
+                    else {
+                        continue;
+                    };
+
+                    let day = parse_day_key(&format!("day_{day}"))?;
+                    config.set_param(day, &name.to_ascii_lowercase(), value);
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if a flag is missing its value or is malformed
+    pub fn from_args<I>(args: I) -> Result<(Config, Vec<String>), anyhow::Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut config = Config::default();
+        let mut positional = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--year" => config.year = Some(value()?.parse()?),
+                "--input-dir" => config.input_dir = Some(value()?.into()),
+                "--cache-dir" => config.cache_dir = Some(value()?.into()),
+                "--session" => config.session = Some(value()?),
+                "--set" => {
+                    let assignment = value()?;
+                    let (key, value) = assignment
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("expected day_XX.param=value"))?;
+                    let (day, name) = key
+                        .split_once('.')
+                        .ok_or_else(|| anyhow!("expected day_XX.param=value"))?;
+
+                    config.set_param(parse_day_key(day)?, name, value.to_string());
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        Ok((config, positional))
+    }
+
+    /// Overrides values in `self` with values set in `other`
+    pub fn merge(&mut self, other: Config) {
+        let Config {
+            year,
+            input_dir,
+            cache_dir,
+            session,
+            days,
+        } = other;
+
+        self.year = year.or(self.year);
+        self.input_dir = input_dir.or(self.input_dir.take());
+        self.cache_dir = cache_dir.or(self.cache_dir.take());
+        self.session = session.or(self.session.take());
+
+        for (day, params) in days {
+            self.days.entry(day).or_default().extend(params);
+        }
+    }
+
+    pub fn set_param(&mut self, day: u8, name: &str, value: String) {
+        self.days
+            .entry(day)
+            .or_default()
+            .insert(name.to_string(), value);
+    }
+
+    #[must_use]
+    pub fn year(&self) -> u16 {
+        self.year.unwrap_or(DEFAULT_YEAR)
+    }
+
+    /// Path to the input of `day`, `arg` (usually a positional command line
+    /// argument) takes precedence over `input_dir/day_XX.txt`, which takes
+    /// precedence over `day_XX/input.txt`
+    #[must_use]
+    pub fn input_path(&self, day: u8, arg: Option<&str>) -> PathBuf {
+        match (arg, &self.input_dir) {
+            (Some(path), _) => path.into(),
+            (None, Some(dir)) => dir.join(format!("day_{day:02}.txt")),
+            (None, None) => Path::new(&format!("day_{day:02}")).join("input.txt"),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if parameter is set but can't be parsed as `T`
+    pub fn param<T>(&self, day: u8, name: &str) -> Result<Option<T>, anyhow::Error>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.days
+            .get(&day)
+            .and_then(|params| params.get(name))
+            .map(|v| {
+                v.parse()
+                    .with_context(|| format!("invalid value of day_{day:02}.{name}: {v}"))
+            })
+            .transpose()
+    }
+
+    /// # Errors
+    ///
+    /// See `param`
+    pub fn param_or<T>(&self, day: u8, name: &str, default: T) -> Result<T, anyhow::Error>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        Ok(self.param(day, name)?.unwrap_or(default))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CONFIG: &str = r#"
+year = 2020
+input_dir = "inputs"
+session = "abc"
+
+[day_09]
+preamble_length = 25
+
+[day_11]
+occupied_seats_limit = 4
+"#;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_config_file() {
+        let config: Config = TEST_CONFIG.parse().unwrap();
+
+        assert_eq!(config.year(), 2020);
+        assert_eq!(config.input_dir, Some(PathBuf::from("inputs")));
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.param(9, "preamble_length").unwrap(), Some(25usize));
+        assert_eq!(config.param::<usize>(9, "missing").unwrap(), None);
+        assert!("[day_99]\na = 1".parse::<Config>().is_err());
+        assert!("unknown = 1".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn test_from_env_vars() {
+        let vars = [
+            ("AOC_YEAR", "2021"),
+            ("AOC_DAY_09_PREAMBLE_LENGTH", "5"),
+            ("HOME", "/root"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let config = Config::from_env_vars(vars).unwrap();
+
+        assert_eq!(config.year(), 2021);
+        assert_eq!(config.param(9, "preamble_length").unwrap(), Some(5usize));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let (config, positional) =
+            Config::from_args(args("--set day_01.target=42 input.txt --year 2019")).unwrap();
+
+        assert_eq!(config.year(), 2019);
+        assert_eq!(config.param(1, "target").unwrap(), Some(42i64));
+        assert_eq!(positional, args("input.txt"));
+        assert!(Config::from_args(args("--year")).is_err());
+        assert!(Config::from_args(args("--set target=1")).is_err());
+    }
+
+    #[test]
+    fn test_merge_layers() {
+        let mut config: Config = TEST_CONFIG.parse().unwrap();
+        let (flags, _) =
+            Config::from_args(args("--input-dir other --set day_09.preamble_length=5")).unwrap();
+        config.merge(flags);
+
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.input_path(9, None), PathBuf::from("other/day_09.txt"));
+        assert_eq!(config.param(9, "preamble_length").unwrap(), Some(5usize));
+        assert_eq!(config.param(11, "occupied_seats_limit").unwrap(), Some(4usize));
+    }
+
+    #[test]
+    fn test_input_path() {
+        let config = Config::default();
+
+        assert_eq!(config.input_path(3, None), PathBuf::from("day_03/input.txt"));
+        assert_eq!(config.input_path(3, Some("x.txt")), PathBuf::from("x.txt"));
+    }
+}
@@ -0,0 +1,124 @@
+use std::{error::Error, fmt, hash::Hash, num::ParseIntError, str::FromStr};
+
+/// Result of an arithmetic operation doesn't fit its integer type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl Error for OverflowError {}
+
+/// Integer types puzzles can be solved over. Arithmetic is checked, so
+/// overflow is reported instead of wrapping in release builds.
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Default
+    + fmt::Debug
+    + fmt::Display
+    + FromStr<Err = ParseIntError>
+    + Send
+    + Sync
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    /// Distance between `self` and `other`, wide enough for every type
+    fn abs_diff(self, other: Self) -> u128;
+
+    /// # Errors
+    ///
+    /// Returns error on overflow
+    fn try_add(self, rhs: Self) -> Result<Self, OverflowError> {
+        self.checked_add(rhs).ok_or(OverflowError)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error on overflow
+    fn try_sub(self, rhs: Self) -> Result<Self, OverflowError> {
+        self.checked_sub(rhs).ok_or(OverflowError)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error on overflow
+    fn try_mul(self, rhs: Self) -> Result<Self, OverflowError> {
+        self.checked_mul(rhs).ok_or(OverflowError)
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                #[allow(clippy::cast_lossless, clippy::unnecessary_cast)]
+                fn abs_diff(self, other: Self) -> u128 {
+                    <$t>::abs_diff(self, other) as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// # Errors
+///
+/// Returns error if the sum overflows `T`
+pub fn checked_sum<T: Integer>(values: impl IntoIterator<Item = T>) -> Result<T, OverflowError> {
+    values.into_iter().try_fold(T::ZERO, T::try_add)
+}
+
+/// # Errors
+///
+/// Returns error if the product overflows `T`
+pub fn checked_product<T: Integer>(
+    values: impl IntoIterator<Item = T>,
+) -> Result<T, OverflowError> {
+    values.into_iter().try_fold(T::ONE, T::try_mul)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(checked_sum([1i64, 2, 3]), Ok(6));
+        assert_eq!(checked_sum([u8::MAX, 1]), Err(OverflowError));
+        assert_eq!(checked_product([1i128 << 64, 1 << 62]), Ok(1 << 126));
+        assert_eq!(checked_product([1i128 << 64, 1 << 64]), Err(OverflowError));
+        assert_eq!(2u64.try_sub(3), Err(OverflowError));
+        assert_eq!(Integer::abs_diff(i64::MIN, i64::MAX), u128::from(u64::MAX));
+    }
+}
@@ -1,3 +1,9 @@
+pub mod config;
+pub mod integer;
+
+pub use config::Config;
+pub use integer::{checked_product, checked_sum, Integer, OverflowError};
+
 #[must_use]
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -0,0 +1,110 @@
+use anyhow::{bail, Context};
+use common::{checked_product, checked_sum, Integer, OverflowError};
+
+/// How the binaries print found entries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Output {
+    /// Product of the entries, the puzzle answer
+    #[default]
+    Product,
+    /// Entries and their sum, `1721 + 299 = 2020`
+    Terms,
+}
+
+impl Output {
+    /// # Errors
+    ///
+    /// Returns error if the product or the sum overflows `T`
+    pub fn format<T: Integer>(self, terms: &[T]) -> Result<String, OverflowError> {
+        Ok(match self {
+            Output::Product => checked_product(terms.iter().copied())?.to_string(),
+            Output::Terms => {
+                let terms_list: Vec<_> = terms.iter().map(ToString::to_string).collect();
+
+                format!(
+                    "{} = {}",
+                    terms_list.join(" + "),
+                    checked_sum(terms.iter().copied())?
+                )
+            }
+        })
+    }
+}
+
+/// Arguments of the binaries left after `Config::load` takes out its flags:
+/// `[--terms] [--target <sum>] [input]`
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub target: Option<i64>,
+    pub input: Option<String>,
+    pub output: Output,
+}
+
+impl Args {
+    /// # Errors
+    ///
+    /// Returns error on unknown flags, malformed target or too many
+    /// arguments
+    pub fn parse(args: Vec<String>) -> Result<Args, anyhow::Error> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--terms" => parsed.output = Output::Terms,
+                "--product" => parsed.output = Output::Product,
+                "--target" => {
+                    let target = args.next().context("missing value for --target")?;
+                    parsed.target = Some(target.parse()?);
+                }
+                flag if flag.starts_with("--") => bail!("unknown flag {}", flag),
+                _ if parsed.input.is_none() => parsed.input = Some(arg),
+                _ => bail!("unexpected argument {}", arg),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let args = |s: &str| Args::parse(s.split_whitespace().map(String::from).collect());
+
+        assert_eq!(args("").unwrap(), Args::default());
+        assert_eq!(
+            args("--terms --target 42 input.txt").unwrap(),
+            Args {
+                target: Some(42),
+                input: Some("input.txt".to_string()),
+                output: Output::Terms,
+            }
+        );
+        assert_eq!(
+            args("2020").unwrap(),
+            Args {
+                target: None,
+                input: Some("2020".to_string()),
+                output: Output::Product,
+            }
+        );
+        assert!(args("--unknown").is_err());
+        assert!(args("--target").is_err());
+        assert!(args("--target x").is_err());
+        assert!(args("a b").is_err());
+
+        assert_eq!(Output::Product.format(&[1721, 299]).unwrap(), "514579");
+        assert_eq!(
+            Output::Terms.format(&[1721, 299]).unwrap(),
+            "1721 + 299 = 2020"
+        );
+        assert_eq!(
+            Output::Product.format(&[u64::MAX - 1, 2]),
+            Err(OverflowError)
+        );
+    }
+}
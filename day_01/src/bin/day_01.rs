@@ -1,21 +1,55 @@
 use std::{env, fs};
 
-use anyhow::Context;
-use day_01::{find_three_sum, find_two_sum, Problem};
+use args::Args;
+use common::{checked_sum, Config};
+use day_01::{
+    find_closest_three_sum, find_closest_two_sum, find_three_sum, find_two_sum, Problem, Reuse,
+    Sorted, TARGET_SUM,
+};
+
+mod args;
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let args = Args::parse(args)?;
+    let input_path = config.input_path(1, args.input.as_deref());
     let content = fs::read_to_string(input_path)?;
     let Problem { mut entries } = content.parse()?;
-    entries.sort_unstable();
+    let entries = Sorted::sort(&mut entries);
 
-    let target_sum = 2020;
+    let target_sum = match args.target {
+        Some(target) => target,
+        None => config.param_or(1, "target", TARGET_SUM)?,
+    };
 
-    let (a, b) = find_two_sum(&entries, target_sum).unwrap();
-    println!("Part 1: {} * {} = {}", a, b, a * b);
+    match find_two_sum(entries, target_sum, Reuse::Distinct) {
+        Some((a, b)) => println!("Part 1: {}", args.output.format(&[a, b])?),
+        None => {
+            let (a, b) = find_closest_two_sum(entries, target_sum, Reuse::Distinct)
+                .ok_or_else(|| anyhow::anyhow!("not enough entries"))?;
+            println!(
+                "Part 1: no exact sum, closest {} + {} = {}",
+                a,
+                b,
+                checked_sum([a, b])?
+            );
+        }
+    }
 
-    let (a, b, c) = find_three_sum(&entries, target_sum).unwrap();
-    println!("Part 2: {} * {} * {} = {}", a, b, c, a * b * c);
+    match find_three_sum(entries, target_sum, Reuse::Distinct) {
+        Some((a, b, c)) => println!("Part 2: {}", args.output.format(&[a, b, c])?),
+        None => {
+            let (a, b, c) = find_closest_three_sum(entries, target_sum, Reuse::Distinct)
+                .ok_or_else(|| anyhow::anyhow!("not enough entries"))?;
+            println!(
+                "Part 2: no exact sum, closest {} + {} + {} = {}",
+                a,
+                b,
+                c,
+                checked_sum([a, b, c])?
+            );
+        }
+    }
 
     Ok(())
 }
@@ -1,19 +1,31 @@
 use std::{env, fs};
 
 use anyhow::Context;
-use day_01::{find_three_sum_naive, find_two_sum_naive, Problem};
+use args::Args;
+use common::Config;
+use day_01::{find_three_sum_naive, find_two_sum_naive, Problem, Reuse, TARGET_SUM};
+
+mod args;
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let args = Args::parse(args)?;
+    let input_path = config.input_path(1, args.input.as_deref());
     let content = fs::read_to_string(input_path)?;
     let Problem { entries } = content.parse()?;
-    let target_sum = 2020;
 
-    let (a, b) = find_two_sum_naive(&entries, target_sum).unwrap();
-    println!("Part 1: {} * {} = {}", a, b, a * b);
+    let target_sum = match args.target {
+        Some(target) => target,
+        None => config.param_or(1, "target", TARGET_SUM)?,
+    };
+
+    let (a, b) = find_two_sum_naive(&entries, target_sum, Reuse::Distinct)
+        .with_context(|| format!("no two entries sum to {target_sum}"))?;
+    println!("Part 1: {}", args.output.format(&[a, b])?);
 
-    let (a, b, c) = find_three_sum_naive(&entries, target_sum).unwrap();
-    println!("Part 2: {} * {} * {} = {}", a, b, c, a * b * c);
+    let (a, b, c) = find_three_sum_naive(&entries, target_sum, Reuse::Distinct)
+        .with_context(|| format!("no three entries sum to {target_sum}"))?;
+    println!("Part 2: {}", args.output.format(&[a, b, c])?);
 
     Ok(())
 }
@@ -1,11 +1,24 @@
-use std::{cmp::Ordering, str::FromStr};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::Deref,
+    str::FromStr,
+};
 
-#[derive(Debug)]
-pub struct Problem {
-    pub entries: Vec<i64>,
+use common::{checked_product, checked_sum, Integer, OverflowError};
+
+/// Sum the puzzle asks for, overridable with `day_01.target` config parameter
+pub const TARGET_SUM: i64 = 2020;
+
+/// Entries are `i64` by default, any `Integer` (e.g. `i128` or `u64`) works
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Problem<T = i64> {
+    pub entries: Vec<T>,
 }
 
-impl FromStr for Problem {
+impl<T: Integer> FromStr for Problem<T> {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -15,28 +28,171 @@ impl FromStr for Problem {
     }
 }
 
-/// Assumes that `arr` is sorted
-#[must_use]
-pub fn find_two_sum(arr: &[i64], sum: i64) -> Option<(i64, i64)> {
-    let mut i = 0;
-    let mut j = arr.len() - 1;
+impl<T: fmt::Display> fmt::Display for Problem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a sum may use the same entry more than once
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Reuse {
+    /// Every entry of a sum is at a different position, as the puzzle asks
+    #[default]
+    Distinct,
+    /// An entry may be used several times, so `1010` alone sums to `2020`
+    WithReplacement,
+}
+
+impl Reuse {
+    /// Position the entry following the one at `i` may start at
+    fn next_from(self, i: usize) -> usize {
+        match self {
+            Reuse::Distinct => i + 1,
+            Reuse::WithReplacement => i,
+        }
+    }
+}
+
+/// Entries known to be sorted in ascending order, required by the
+/// two-pointer finders so they can't be given unsorted entries by mistake
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sorted<T>(T);
+
+impl<'a, T: Ord> Sorted<&'a [T]> {
+    /// Returns `None` if `arr` is not sorted
+    #[must_use]
+    pub fn new(arr: &'a [T]) -> Option<Self> {
+        arr.windows(2).all(|w| w[0] <= w[1]).then_some(Sorted(arr))
+    }
+
+    pub fn sort(arr: &'a mut [T]) -> Self {
+        arr.sort_unstable();
+
+        Sorted(arr)
+    }
+}
 
-    while i < j {
-        match (arr[i] + arr[j]).cmp(&sum) {
+impl<T> Deref for Sorted<&[T]> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+/// What the remaining entries have to sum to after taking `terms` out of
+/// `sum`, `None` if it overflows `T` so no entries can. Non-negative terms
+/// are taken out while the partial result isn't negative and negative terms
+/// while it is, so it stays in range. Once only terms moving it away from
+/// zero are left, it only overflows if the result does.
+fn remainder<T: Integer>(sum: T, terms: impl IntoIterator<Item = T>) -> Option<T> {
+    let (mut negative, mut non_negative): (Vec<_>, Vec<_>) =
+        terms.into_iter().partition(|&t| t < T::ZERO);
+    let mut rest = sum;
+
+    loop {
+        let next = if rest < T::ZERO {
+            negative.pop().or_else(|| non_negative.pop())
+        } else {
+            non_negative.pop().or_else(|| negative.pop())
+        };
+
+        match next {
+            Some(term) => rest = rest.checked_sub(term)?,
+            None => return Some(rest),
+        }
+    }
+}
+
+/// Sum of `terms`, or on which side of the values of `T` it lies if it
+/// overflows. The smallest and largest terms are added first, so partial
+/// sums stay in range whenever the total does.
+fn sum_of<T: Integer>(mut terms: [T; 3]) -> Result<T, Ordering> {
+    terms.sort_unstable();
+    let [low, mid, high] = terms;
+
+    low.checked_add(high)
+        .and_then(|s| s.checked_add(mid))
+        .ok_or(if mid > T::ZERO {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        })
+}
+
+/// Two-pointer scan of `arr[from..]` towards two entries summing with
+/// `offset` to `sum`, calls `visit` with positions of every pair it passes
+/// and their total. Stops at the first pair hitting `sum`, no other pair can
+/// be closer to it. Pairs overflowing `T` aren't visited, they only steer the
+/// scan like any other sum past `sum`.
+fn scan_pairs<T: Integer>(
+    arr: Sorted<&[T]>,
+    from: usize,
+    offset: T,
+    sum: T,
+    reuse: Reuse,
+    mut visit: impl FnMut(usize, usize, T),
+) {
+    let mut i = from;
+    let Some(mut j) = arr.len().checked_sub(1) else {
+        return;
+    };
+
+    while reuse.next_from(i) <= j {
+        let ordering = match sum_of([offset, arr[i], arr[j]]) {
+            Ok(total) => {
+                visit(i, j, total);
+                total.cmp(&sum)
+            }
+            Err(ordering) => ordering,
+        };
+
+        match ordering {
             Ordering::Less => i += 1,
-            Ordering::Equal => return Some((arr[i], arr[j])),
+            Ordering::Equal => return,
+            Ordering::Greater if j == 0 => return,
             Ordering::Greater => j -= 1,
         }
     }
+}
 
-    None
+/// Returns positions of two entries of `arr[from..]` summing with `offset`
+/// to `sum`
+fn two_pointer<T: Integer>(
+    arr: Sorted<&[T]>,
+    from: usize,
+    offset: T,
+    sum: T,
+    reuse: Reuse,
+) -> Option<(usize, usize)> {
+    let mut found = None;
+
+    scan_pairs(arr, from, offset, sum, reuse, |i, j, total| {
+        if total == sum {
+            found = Some((i, j));
+        }
+    });
+
+    found
 }
 
+/// Sums overflowing `T` can't hit `sum` and are skipped
 #[must_use]
-pub fn find_two_sum_naive(arr: &[i64], sum: i64) -> Option<(i64, i64)> {
+pub fn find_two_sum<T: Integer>(arr: Sorted<&[T]>, sum: T, reuse: Reuse) -> Option<(T, T)> {
+    two_pointer(arr, 0, T::ZERO, sum, reuse).map(|(i, j)| (arr[i], arr[j]))
+}
+
+/// Sums overflowing `T` can't hit `sum` and are skipped
+#[must_use]
+pub fn find_two_sum_naive<T: Integer>(arr: &[T], sum: T, reuse: Reuse) -> Option<(T, T)> {
     for (i, &a) in arr.iter().enumerate() {
-        for &b in &arr[i..] {
-            if b == sum - a {
+        for &b in &arr[reuse.next_from(i)..] {
+            if a.checked_add(b) == Some(sum) {
                 return Some((a, b));
             }
         }
@@ -45,29 +201,434 @@ pub fn find_two_sum_naive(arr: &[i64], sum: i64) -> Option<(i64, i64)> {
     None
 }
 
-/// Assumes that `arr` is sorted
+/// Sums overflowing `T` can't hit `sum` and are skipped
 #[must_use]
-pub fn find_three_sum(arr: &[i64], sum: i64) -> Option<(i64, i64, i64)> {
+pub fn find_three_sum<T: Integer>(arr: Sorted<&[T]>, sum: T, reuse: Reuse) -> Option<(T, T, T)> {
     for (i, &a) in arr.iter().enumerate() {
-        if let Some((b, c)) = find_two_sum(&arr[i..], sum - a) {
-            return Some((a, b, c));
+        if let Some((j, l)) = two_pointer(arr, reuse.next_from(i), a, sum, reuse) {
+            return Some((a, arr[j], arr[l]));
         }
     }
 
     None
 }
 
+/// Sums overflowing `T` can't hit `sum` and are skipped
 #[must_use]
-pub fn find_three_sum_naive(arr: &[i64], sum: i64) -> Option<(i64, i64, i64)> {
+pub fn find_three_sum_naive<T: Integer>(arr: &[T], sum: T, reuse: Reuse) -> Option<(T, T, T)> {
     for (i, &a) in arr.iter().enumerate() {
-        if let Some((b, c)) = find_two_sum_naive(&arr[i..], sum - a) {
-            return Some((a, b, c));
+        let rest = &arr[reuse.next_from(i)..];
+
+        for (j, &b) in rest.iter().enumerate() {
+            for &c in &rest[reuse.next_from(j)..] {
+                if sum_of([a, b, c]) == Ok(sum) {
+                    return Some((a, b, c));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// What makes a sum the best one when no sum hits the target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Approximation {
+    /// Sum with the smallest distance to the target
+    Closest,
+    /// Largest sum not exceeding the target
+    AtMost,
+}
+
+impl Approximation {
+    fn is_better<T: Integer>(self, target: T, candidate: T, best: Option<T>) -> bool {
+        match (self, best) {
+            (Approximation::AtMost, _) if candidate > target => false,
+            (_, None) => true,
+            (Approximation::Closest, Some(b)) => candidate.abs_diff(target) < b.abs_diff(target),
+            (Approximation::AtMost, Some(b)) => candidate > b,
+        }
+    }
+}
+
+/// Returns positions of `k` (two or three) entries with the best sum by
+/// `approximation`, the first one found wins ties
+fn approximate<T: Integer>(
+    arr: Sorted<&[T]>,
+    k: usize,
+    sum: T,
+    reuse: Reuse,
+    approximation: Approximation,
+) -> Option<Vec<usize>> {
+    let mut best: Option<(T, Vec<usize>)> = None;
+    let firsts: Vec<Option<usize>> = match k {
+        2 => vec![None],
+        _ => (0..arr.len()).map(Some).collect(),
+    };
+
+    for first in firsts {
+        let (from, offset) = first.map_or((0, T::ZERO), |f| (reuse.next_from(f), arr[f]));
+
+        scan_pairs(arr, from, offset, sum, reuse, |i, j, total| {
+            if approximation.is_better(sum, total, best.as_ref().map(|b| b.0)) {
+                best = Some((total, first.into_iter().chain([i, j]).collect()));
+            }
+        });
+
+        if best.as_ref().is_some_and(|b| b.0 == sum) {
+            break;
+        }
+    }
+
+    best.map(|(_, positions)| positions)
+}
+
+/// Pair of entries whose sum is closest to `sum`, sums overflowing `T`
+/// are skipped
+#[must_use]
+pub fn find_closest_two_sum<T: Integer>(arr: Sorted<&[T]>, sum: T, reuse: Reuse) -> Option<(T, T)> {
+    approximate(arr, 2, sum, reuse, Approximation::Closest).map(|p| (arr[p[0]], arr[p[1]]))
+}
+
+/// Triple of entries whose sum is closest to `sum`, sums overflowing `T`
+/// are skipped
+#[must_use]
+pub fn find_closest_three_sum<T: Integer>(
+    arr: Sorted<&[T]>,
+    sum: T,
+    reuse: Reuse,
+) -> Option<(T, T, T)> {
+    approximate(arr, 3, sum, reuse, Approximation::Closest)
+        .map(|p| (arr[p[0]], arr[p[1]], arr[p[2]]))
+}
+
+/// Pair of entries with the largest sum not exceeding `sum`, sums overflowing `T`
+/// are skipped
+#[must_use]
+pub fn find_two_sum_at_most<T: Integer>(arr: Sorted<&[T]>, sum: T, reuse: Reuse) -> Option<(T, T)> {
+    approximate(arr, 2, sum, reuse, Approximation::AtMost).map(|p| (arr[p[0]], arr[p[1]]))
+}
+
+/// Triple of entries with the largest sum not exceeding `sum`, sums overflowing `T`
+/// are skipped
+#[must_use]
+pub fn find_three_sum_at_most<T: Integer>(
+    arr: Sorted<&[T]>,
+    sum: T,
+    reuse: Reuse,
+) -> Option<(T, T, T)> {
+    approximate(arr, 3, sum, reuse, Approximation::AtMost)
+        .map(|p| (arr[p[0]], arr[p[1]], arr[p[2]]))
+}
+
+/// Looks up `complement(b)` of every entry `b` among the entries before it
+fn hashed_pair<T: Integer>(
+    arr: &[T],
+    reuse: Reuse,
+    complement: impl Fn(T) -> Option<T>,
+) -> Option<(T, T)> {
+    let mut seen = HashSet::with_capacity(arr.len());
+
+    for &b in arr {
+        if reuse == Reuse::WithReplacement {
+            seen.insert(b);
         }
+
+        if let Some(a) = complement(b) {
+            if seen.contains(&a) {
+                return Some((a, b));
+            }
+        }
+
+        seen.insert(b);
     }
 
     None
 }
 
+/// Looks up complement of every entry among the entries before it, `arr`
+/// does not have to be sorted. Returns entries in the order they appear.
+/// Complements overflowing `T` can't be entries and are skipped.
+#[must_use]
+pub fn find_two_sum_hashed<T: Integer>(arr: &[T], sum: T, reuse: Reuse) -> Option<(T, T)> {
+    hashed_pair(arr, reuse, |b| remainder(sum, [b]))
+}
+
+/// `find_two_sum_hashed` for entries after every entry, `arr` does not have
+/// to be sorted
+#[must_use]
+pub fn find_three_sum_hashed<T: Integer>(arr: &[T], sum: T, reuse: Reuse) -> Option<(T, T, T)> {
+    arr.iter().enumerate().find_map(|(i, &a)| {
+        hashed_pair(&arr[reuse.next_from(i)..], reuse, |c| {
+            remainder(sum, [a, c])
+        })
+        .map(|(b, c)| (a, b, c))
+    })
+}
+
+/// Advances `c` to the next combination of `c.len()` elements of `0..n` in
+/// lexicographic order, returns `false` if `c` was the last one
+fn next_combination(c: &mut [usize], n: usize) -> bool {
+    let k = c.len();
+
+    for i in (0..k).rev() {
+        if c[i] < n - k + i {
+            c[i] += 1;
+
+            for j in (i + 1)..k {
+                c[j] = c[j - 1] + 1;
+            }
+
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Entries found by `find_k_sum`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KSum<T = i64> {
+    /// Positions of the entries in the searched slice, in increasing order
+    pub indices: Vec<usize>,
+    /// Entries, in the order of `indices`
+    pub values: Vec<T>,
+}
+
+impl<T: Integer> KSum<T> {
+    fn from_indices(arr: &[T], mut indices: Vec<usize>) -> Self {
+        indices.sort_unstable();
+        let values = indices.iter().map(|&i| arr[i]).collect();
+
+        KSum { indices, values }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if the product overflows `T`
+    pub fn product(&self) -> Result<T, OverflowError> {
+        checked_product(self.values.iter().copied())
+    }
+}
+
+/// Splits `k` entries into two halves, builds a map of sums of all
+/// combinations of the first half and looks up the remainder of every
+/// combination of the second half. First halves whose sums overflow `T` are
+/// skipped. Assumes that `arr` is sorted and `2 <= k <= arr.len()`.
+fn meet_in_the_middle<T: Integer>(arr: &[T], k: usize, sum: T) -> Option<Vec<usize>> {
+    let n = arr.len();
+    let left_k = k / 2;
+    let right_k = k - left_k;
+    let combination_sum = |c: &[usize]| checked_sum(c.iter().map(|&i| arr[i]));
+
+    let mut left_sums: HashMap<T, Vec<Vec<usize>>> = HashMap::new();
+    let mut left: Vec<usize> = (0..left_k).collect();
+
+    loop {
+        if let Ok(left_sum) = combination_sum(&left) {
+            left_sums.entry(left_sum).or_default().push(left.clone());
+        }
+
+        if !next_combination(&mut left, n) {
+            break;
+        }
+    }
+
+    let mut right: Vec<usize> = (0..right_k).collect();
+
+    loop {
+        // halves can't share entries, so every position of the left half has
+        // to precede the positions of the right half
+        let left = remainder(sum, right.iter().map(|&i| arr[i]))
+            .and_then(|left_sum| left_sums.get(&left_sum))
+            .and_then(|lefts| lefts.iter().find(|l| l[left_k - 1] < right[0]));
+
+        if let Some(left) = left {
+            return Some(left.iter().chain(&right).copied().collect());
+        }
+
+        if !next_combination(&mut right, n) {
+            return None;
+        }
+    }
+}
+
+/// Finds `k` entries of `arr` (at distinct positions) summing to `sum`, `arr`
+/// does not have to be sorted. Two and three entries are found with
+/// two-pointer scan over sorted entries, more with meet-in-the-middle.
+/// Sums overflowing `T` are skipped. Combinations of more than three entries
+/// are skipped if the sum of their first half overflows `T`.
+#[must_use]
+pub fn find_k_sum<T: Integer>(arr: &[T], k: usize, sum: T) -> Option<KSum<T>> {
+    let mut order: Vec<usize> = (0..arr.len()).collect();
+    order.sort_unstable_by_key(|&i| arr[i]);
+    let sorted: Vec<T> = order.iter().map(|&i| arr[i]).collect();
+    let sorted = Sorted(sorted.as_slice());
+
+    let positions = match k {
+        _ if k > arr.len() => None,
+        0 => (sum == T::ZERO).then(Vec::new),
+        1 => sorted.binary_search(&sum).ok().map(|i| vec![i]),
+        2 => two_pointer(sorted, 0, T::ZERO, sum, Reuse::Distinct).map(|(i, j)| vec![i, j]),
+        3 => {
+            let mut found = None;
+
+            for i in 0..(sorted.len() - 2) {
+                if let Some((j, l)) = two_pointer(sorted, i + 1, sorted[i], sum, Reuse::Distinct) {
+                    found = Some(vec![i, j, l]);
+                    break;
+                }
+            }
+
+            found
+        }
+        _ => meet_in_the_middle(&sorted, k, sum),
+    };
+
+    positions.map(|positions| {
+        let indices = positions.into_iter().map(|p| order[p]).collect();
+
+        KSum::from_indices(arr, indices)
+    })
+}
+
+/// Iterator over every combination of entries summing to a value, see
+/// `find_all_k_sums`
+#[derive(Debug)]
+pub struct KSums<'a, T = i64> {
+    arr: &'a [T],
+    k: usize,
+    sum: T,
+    /// positions of every value in `arr`, in increasing order
+    positions: HashMap<T, Vec<usize>>,
+    /// first `k - 1` positions of current combination, `None` when exhausted
+    prefix: Option<Vec<usize>>,
+    /// skip candidates for the last position before this index in
+    /// `positions` of the remainder
+    cursor: usize,
+}
+
+impl<'a, T: Integer> KSums<'a, T> {
+    fn new(arr: &'a [T], k: usize, sum: T) -> Self {
+        let mut positions: HashMap<T, Vec<usize>> = HashMap::new();
+
+        for (i, &v) in arr.iter().enumerate() {
+            positions.entry(v).or_default().push(i);
+        }
+
+        KSums {
+            arr,
+            k,
+            sum,
+            positions,
+            prefix: (k <= arr.len()).then(|| (0..k.saturating_sub(1)).collect()),
+            cursor: 0,
+        }
+    }
+
+    /// Candidates for the last position of a combination starting with
+    /// current prefix, none if the remainder overflows `T`
+    fn candidates(&self, prefix: &[usize]) -> &[usize] {
+        let start = prefix.last().map_or(0, |&last| last + 1);
+
+        let Some(positions) = remainder(self.sum, prefix.iter().map(|&i| self.arr[i]))
+            .and_then(|rest| self.positions.get(&rest))
+        else {
+            return &[];
+        };
+
+        let from = positions.partition_point(|&p| p < start).max(self.cursor);
+
+        positions.get(from..).unwrap_or(&[])
+    }
+
+    fn advance_prefix(&mut self) {
+        let n = self.arr.len();
+        self.cursor = 0;
+
+        if let Some(prefix) = self.prefix.as_mut() {
+            if !next_combination(prefix, n) {
+                self.prefix = None;
+            }
+        }
+    }
+
+    /// Counts remaining combinations without building them
+    #[must_use]
+    pub fn count_matches(mut self) -> usize {
+        if self.k == 0 {
+            return self.next().map_or(0, |_| 1);
+        }
+
+        let mut count = 0;
+
+        while let Some(prefix) = self.prefix.as_ref() {
+            count += self.candidates(prefix).len();
+            self.advance_prefix();
+        }
+
+        count
+    }
+}
+
+impl<T: Integer> Iterator for KSums<'_, T> {
+    type Item = KSum<T>;
+
+    /// Combinations overflowing `T` can't hit the sum and are skipped
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.k == 0 {
+            self.prefix.take()?;
+            return (self.sum == T::ZERO).then(KSum::default);
+        }
+
+        loop {
+            let prefix = self.prefix.as_ref()?;
+
+            if let Some(&last) = self.candidates(prefix).first() {
+                let mut indices = prefix.clone();
+                indices.push(last);
+
+                let positions = &self.positions[&self.arr[last]];
+                self.cursor = positions.partition_point(|&p| p <= last);
+
+                return Some(KSum::from_indices(self.arr, indices));
+            }
+
+            self.advance_prefix();
+        }
+    }
+}
+
+/// Iterates over every combination of `k` entries of `arr` (at distinct
+/// positions) summing to `sum`, `arr` does not have to be sorted.
+/// Combinations are yielded in lexicographic order of their positions, so
+/// equal values at different positions make different combinations. Use
+/// `count_matches` to count combinations without building them.
+#[must_use]
+pub fn find_all_k_sums<T: Integer>(arr: &[T], k: usize, sum: T) -> KSums<'_, T> {
+    KSums::new(arr, k, sum)
+}
+
+/// See `find_all_k_sums`
+pub fn find_all_two_sums<T: Integer>(arr: &[T], sum: T) -> impl Iterator<Item = (T, T)> + '_ {
+    find_all_k_sums(arr, 2, sum).map(|s| (s.values[0], s.values[1]))
+}
+
+/// See `find_all_k_sums`
+pub fn find_all_three_sums<T: Integer>(arr: &[T], sum: T) -> impl Iterator<Item = (T, T, T)> + '_ {
+    find_all_k_sums(arr, 3, sum).map(|s| (s.values[0], s.values[1], s.values[2]))
+}
+
+#[must_use]
+pub fn count_two_sums<T: Integer>(arr: &[T], sum: T) -> usize {
+    find_all_k_sums(arr, 2, sum).count_matches()
+}
+
+#[must_use]
+pub fn count_three_sums<T: Integer>(arr: &[T], sum: T) -> usize {
+    find_all_k_sums(arr, 3, sum).count_matches()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,43 +643,358 @@ mod tests {
 
     #[test]
     fn test_find_two_sum() {
-        let Problem { mut entries } = TEST_INPUT.parse().unwrap();
-        entries.sort();
+        let Problem { mut entries } = TEST_INPUT.parse::<Problem>().unwrap();
+        let entries = Sorted::sort(&mut entries);
 
         assert_eq!(
-            find_two_sum(&entries, 2020).map(|(a, b)| a * b),
+            find_two_sum(entries, 2020, Reuse::Distinct).map(|(a, b)| a * b),
             Some(514579)
         );
     }
 
     #[test]
     fn test_find_three_sum() {
-        let Problem { mut entries } = TEST_INPUT.parse().unwrap();
-        entries.sort();
+        let Problem { mut entries } = TEST_INPUT.parse::<Problem>().unwrap();
+        let entries = Sorted::sort(&mut entries);
 
         assert_eq!(
-            find_three_sum(&entries, 2020).map(|(a, b, c)| a * b * c),
+            find_three_sum(entries, 2020, Reuse::Distinct).map(|(a, b, c)| a * b * c),
             Some(241861950)
         );
     }
 
     #[test]
     fn test_find_two_sum_naive() {
-        let Problem { entries } = TEST_INPUT.parse().unwrap();
+        let Problem { entries } = TEST_INPUT.parse::<Problem>().unwrap();
 
         assert_eq!(
-            find_two_sum_naive(&entries, 2020).map(|(a, b)| a * b),
+            find_two_sum_naive(&entries, 2020, Reuse::Distinct).map(|(a, b)| a * b),
             Some(514579)
         );
     }
 
     #[test]
     fn test_find_three_sum_naive() {
-        let Problem { entries } = TEST_INPUT.parse().unwrap();
+        let Problem { entries } = TEST_INPUT.parse::<Problem>().unwrap();
+
+        assert_eq!(
+            find_three_sum_naive(&entries, 2020, Reuse::Distinct).map(|(a, b, c)| a * b * c),
+            Some(241861950)
+        );
+    }
+
+    #[test]
+    fn test_find_sums_hashed() {
+        let Problem { entries } = TEST_INPUT.parse::<Problem>().unwrap();
 
         assert_eq!(
-            find_three_sum_naive(&entries, 2020).map(|(a, b, c)| a * b * c),
+            find_two_sum_hashed(&entries, 2020, Reuse::Distinct),
+            Some((1721, 299))
+        );
+        assert_eq!(
+            find_three_sum_hashed(&entries, 2020, Reuse::Distinct).map(|(a, b, c)| a * b * c),
             Some(241861950)
         );
+        assert_eq!(find_two_sum_hashed(&entries, 1, Reuse::Distinct), None);
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut entries = vec![3, 1, 2];
+
+        assert_eq!(Sorted::new(&entries), None);
+        assert_eq!(*Sorted::sort(&mut entries), [1, 2, 3]);
+        assert!(Sorted::new(&entries).is_some());
+        assert!(Sorted::<&[i64]>::new(&[]).is_some());
+    }
+
+    #[test]
+    fn test_approximate_sums() {
+        let mut entries = vec![10, 22, 28, 29, 30, 40];
+        let sorted = Sorted::sort(&mut entries);
+
+        assert_eq!(find_two_sum(sorted, 54, Reuse::Distinct), None);
+        assert_eq!(
+            find_closest_two_sum(sorted, 54, Reuse::Distinct),
+            Some((22, 30))
+        );
+        assert_eq!(
+            find_two_sum_at_most(sorted, 54, Reuse::Distinct),
+            Some((22, 30))
+        );
+        assert_eq!(
+            find_closest_two_sum(sorted, 55, Reuse::Distinct),
+            Some((28, 29))
+        );
+        assert_eq!(
+            find_two_sum_at_most(sorted, 55, Reuse::Distinct),
+            Some((22, 30))
+        );
+        assert_eq!(
+            find_closest_two_sum(sorted, 100, Reuse::WithReplacement),
+            Some((40, 40))
+        );
+        assert_eq!(find_two_sum_at_most(sorted, 31, Reuse::Distinct), None);
+
+        assert_eq!(
+            find_closest_three_sum(sorted, 58, Reuse::Distinct),
+            Some((10, 22, 28))
+        );
+        assert_eq!(
+            find_three_sum_at_most(sorted, 75, Reuse::Distinct),
+            Some((10, 22, 40))
+        );
+        assert_eq!(
+            find_closest_three_sum(sorted, 1000, Reuse::Distinct),
+            Some((29, 30, 40))
+        );
+        assert_eq!(find_three_sum_at_most(sorted, 59, Reuse::Distinct), None);
+        assert_eq!(find_closest_two_sum(Sorted(&[1]), 2, Reuse::Distinct), None);
+    }
+
+    #[test]
+    fn test_reuse() {
+        let entries = [1, 1010];
+        let sorted = Sorted::new(&entries).unwrap();
+
+        assert_eq!(find_two_sum(sorted, 2020, Reuse::Distinct), None);
+        assert_eq!(find_two_sum_naive(&entries, 2020, Reuse::Distinct), None);
+        assert_eq!(
+            find_two_sum(sorted, 2020, Reuse::WithReplacement),
+            Some((1010, 1010))
+        );
+        assert_eq!(
+            find_two_sum_naive(&entries, 2020, Reuse::WithReplacement),
+            Some((1010, 1010))
+        );
+
+        assert_eq!(find_three_sum(sorted, 2021, Reuse::Distinct), None);
+        assert_eq!(find_three_sum_naive(&entries, 2021, Reuse::Distinct), None);
+        assert_eq!(
+            find_three_sum(sorted, 2021, Reuse::WithReplacement),
+            Some((1, 1010, 1010))
+        );
+        assert_eq!(
+            find_three_sum_naive(&entries, 3030, Reuse::WithReplacement),
+            Some((1010, 1010, 1010))
+        );
+        assert_eq!(find_three_sum(sorted, 1011, Reuse::Distinct), None);
+        assert_eq!(find_two_sum_hashed(&entries, 2020, Reuse::Distinct), None);
+        assert_eq!(
+            find_three_sum_hashed(&entries, 2021, Reuse::WithReplacement),
+            Some((1, 1010, 1010))
+        );
+    }
+
+    #[test]
+    fn test_empty_and_singleton() {
+        for reuse in [Reuse::Distinct, Reuse::WithReplacement] {
+            assert_eq!(find_two_sum(Sorted(&[]), 0, reuse), None);
+            assert_eq!(find_two_sum_naive(&[], 0, reuse), None);
+            assert_eq!(find_three_sum(Sorted(&[]), 0, reuse), None);
+            assert_eq!(find_three_sum_naive(&[], 0, reuse), None);
+            assert_eq!(find_two_sum_hashed(&[], 0, reuse), None);
+            assert_eq!(find_three_sum_hashed(&[], 0, reuse), None);
+        }
+
+        assert_eq!(find_two_sum(Sorted(&[1]), 2, Reuse::Distinct), None);
+        assert_eq!(find_three_sum_naive(&[1], 3, Reuse::Distinct), None);
+        assert_eq!(find_two_sum_hashed(&[1], 2, Reuse::Distinct), None);
+        assert_eq!(
+            find_two_sum(Sorted(&[1]), 2, Reuse::WithReplacement),
+            Some((1, 1))
+        );
+        assert_eq!(
+            find_two_sum_hashed(&[1], 2, Reuse::WithReplacement),
+            Some((1, 1))
+        );
+        assert_eq!(
+            find_three_sum(Sorted(&[1]), 3, Reuse::WithReplacement),
+            Some((1, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let entries: [u64; 3] = [1, 3, u64::MAX - 1];
+        let sorted = Sorted::new(&entries).unwrap();
+
+        assert_eq!(
+            find_two_sum(sorted, u64::MAX, Reuse::Distinct),
+            Some((1, u64::MAX - 1))
+        );
+        assert_eq!(find_two_sum(sorted, 1, Reuse::Distinct), None);
+
+        let entries: [u64; 3] = [2, 3, u64::MAX - 1];
+        let sorted = Sorted::new(&entries).unwrap();
+
+        assert_eq!(find_two_sum(sorted, 10, Reuse::Distinct), None);
+        assert_eq!(find_two_sum_naive(&entries, 10, Reuse::Distinct), None);
+        assert_eq!(find_two_sum(sorted, 5, Reuse::Distinct), Some((2, 3)));
+
+        // unsigned complements below zero can't match, they don't overflow
+        assert_eq!(
+            find_two_sum_hashed(&entries, 5, Reuse::Distinct),
+            Some((2, 3))
+        );
+        assert_eq!(find_all_k_sums(&entries, 3, 5).count(), 0);
+
+        // overflowing candidates don't stop the search for valid ones
+        let entries = [1, 2019, i64::MAX];
+        let sorted = Sorted::new(&entries).unwrap();
+
+        assert_eq!(find_two_sum(sorted, 2020, Reuse::Distinct), Some((1, 2019)));
+        assert_eq!(
+            find_two_sum_naive(&entries, 2020, Reuse::Distinct),
+            Some((1, 2019))
+        );
+        assert_eq!(
+            find_closest_two_sum(sorted, 2021, Reuse::Distinct),
+            Some((1, 2019))
+        );
+        assert_eq!(
+            find_k_sum(&[i64::MAX, 1, 2019, i64::MAX], 2, 2020).map(|s| s.indices),
+            Some(vec![1, 2])
+        );
+
+        let entries: [u64; 3] = [1, 2019, u64::MAX];
+        let sorted = Sorted::new(&entries).unwrap();
+
+        assert_eq!(find_two_sum(sorted, 2020, Reuse::Distinct), Some((1, 2019)));
+        assert_eq!(find_three_sum(sorted, 2020, Reuse::Distinct), None);
+
+        let entries = [i64::MIN, -1, 1, 2, 2017, i64::MAX];
+        let sorted = Sorted::new(&entries).unwrap();
+
+        assert_eq!(
+            find_three_sum(sorted, 2020, Reuse::Distinct),
+            Some((1, 2, 2017))
+        );
+        assert_eq!(
+            find_three_sum_naive(&entries, -2, Reuse::Distinct),
+            Some((i64::MIN, -1, i64::MAX))
+        );
+        assert_eq!(
+            find_k_sum(&entries, 4, 2019).map(|s| s.values),
+            Some(vec![-1, 1, 2, 2017])
+        );
+
+        // overflowing complements and prefixes of signed entries are skipped
+        let entries = [i64::MIN, 1, 2019];
+
+        assert_eq!(
+            find_two_sum_hashed(&entries, 2020, Reuse::Distinct),
+            Some((1, 2019))
+        );
+        assert_eq!(
+            find_all_k_sums(&entries, 2, 2020)
+                .map(|s| s.values)
+                .collect::<Vec<_>>(),
+            vec![vec![1, 2019]]
+        );
+        assert_eq!(count_three_sums(&[1, 2, 2017, i64::MAX], 2020), 1);
+        assert_eq!(
+            find_three_sum_hashed(&[-128i8, 64, 64], 0, Reuse::Distinct),
+            Some((-128, 64, 64))
+        );
+        assert_eq!(find_all_k_sums(&[-128i8, -1, 64, 64], 4, -1).count(), 1);
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        let p: Problem<i128> = "100000000000000000000\n-3\n200000000000000000000"
+            .parse()
+            .unwrap();
+        let sum = 300_000_000_000_000_000_000i128;
+
+        let found = find_k_sum(&p.entries, 2, sum).unwrap();
+        assert_eq!(found.indices, vec![0, 2]);
+        assert_eq!(found.product(), Err(OverflowError));
+        assert_eq!(
+            find_three_sum_hashed(&p.entries, sum - 3, Reuse::Distinct).map(|(a, b, c)| a + b + c),
+            Some(sum - 3)
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse::<Problem>().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
+
+    #[test]
+    fn test_find_k_sum() {
+        let Problem { entries } = TEST_INPUT.parse::<Problem>().unwrap();
+
+        let two = find_k_sum(&entries, 2, 2020).unwrap();
+        assert_eq!(two.indices, vec![0, 3]);
+        assert_eq!(two.values, vec![1721, 299]);
+        assert_eq!(two.product().unwrap(), 514579);
+
+        let three = find_k_sum(&entries, 3, 2020).unwrap();
+        assert_eq!(three.indices, vec![1, 2, 4]);
+        assert_eq!(three.product().unwrap(), 241861950);
+
+        assert_eq!(find_k_sum(&entries, 1, 366).unwrap().indices, vec![2]);
+        assert_eq!(find_k_sum(&entries, 0, 0), Some(KSum::default()));
+        assert_eq!(find_k_sum(&entries, 7, 2020), None);
+    }
+
+    #[test]
+    fn test_find_k_sum_meet_in_the_middle() {
+        let entries = [8, 1, 7, 2, 6, 3, 5, 4, 10, 9];
+
+        let four = find_k_sum(&entries, 4, 34).unwrap();
+        assert_eq!(four.indices, vec![0, 2, 8, 9]);
+        assert_eq!(four.values, vec![8, 7, 10, 9]);
+
+        let five = find_k_sum(&entries, 5, 15).unwrap();
+        assert_eq!(five.values.iter().sum::<i64>(), 15);
+        assert_eq!(five.indices, vec![1, 3, 5, 6, 7]);
+
+        assert_eq!(find_k_sum(&entries, 4, 35), None);
+        assert_eq!(find_k_sum(&entries, 5, 14), None);
+    }
+
+    #[test]
+    fn test_find_all_two_sums() {
+        let Problem { entries } = TEST_INPUT.parse::<Problem>().unwrap();
+
+        assert_eq!(
+            find_all_two_sums(&entries, 2020).collect::<Vec<_>>(),
+            vec![(1721, 299)]
+        );
+        assert_eq!(count_two_sums(&entries, 2020), 1);
+        assert_eq!(count_two_sums(&entries, 1), 0);
+    }
+
+    #[test]
+    fn test_find_all_k_sums_with_duplicates() {
+        let entries = [5, 5, 1, 5, 9];
+
+        let indices = find_all_k_sums(&entries, 2, 10)
+            .map(|s| s.indices)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            indices,
+            vec![vec![0, 1], vec![0, 3], vec![1, 3], vec![2, 4]]
+        );
+        assert_eq!(count_two_sums(&entries, 10), 4);
+    }
+
+    #[test]
+    fn test_find_all_three_sums() {
+        let entries = [1, 2, 3, 4, 5, 6];
+
+        assert_eq!(
+            find_all_three_sums(&entries, 10).collect::<Vec<_>>(),
+            vec![(1, 3, 6), (1, 4, 5), (2, 3, 5)]
+        );
+        assert_eq!(count_three_sums(&entries, 10), 3);
+        assert_eq!(find_all_k_sums(&entries, 6, 21).count(), 1);
+        assert_eq!(find_all_k_sums(&entries, 7, 21).count(), 0);
     }
 }
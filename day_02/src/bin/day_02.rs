@@ -1,18 +1,52 @@
-use std::{env, fs};
+use std::{
+    env,
+    fs::{self, File},
+    io::BufReader,
+    num::NonZeroUsize,
+    thread,
+};
 
-use anyhow::Context;
-use day_02::{count_valid_passwords, validate_password, validate_password_new_policy, Problem};
+use common::Config;
+use day_02::{
+    count_valid_passwords_parallel, find_violations, CountPolicy, PositionPolicy, Problem,
+    DEFAULT_CHUNK_SIZE,
+};
+
+const USAGE: &str = "Usage: day_02 [--report] [input]";
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
-    let content = fs::read_to_string(input_path)?;
-    let Problem { passwords } = content.parse()?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let (flags, positional): (Vec<_>, Vec<_>) = args.iter().partition(|a| a.starts_with("--"));
+    let report = match flags.as_slice() {
+        [] => false,
+        [flag] if *flag == "--report" => true,
+        _ => anyhow::bail!(USAGE),
+    };
+
+    let input_path = config.input_path(2, positional.first().map(|s| s.as_str()));
+
+    if report {
+        let content = fs::read_to_string(input_path)?;
+        let Problem { passwords } = content.parse()?;
+
+        for violation in find_violations(&passwords, &[&CountPolicy, &PositionPolicy::default()]) {
+            println!("{violation}");
+        }
+
+        return Ok(());
+    }
 
-    let count = count_valid_passwords(&passwords, validate_password);
-    println!("Part 1: {count}");
+    // inputs can be too large to read at once, validate them in chunks
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let counts = count_valid_passwords_parallel(
+        BufReader::new(File::open(input_path)?),
+        &[&CountPolicy, &PositionPolicy::default()],
+        threads,
+        DEFAULT_CHUNK_SIZE,
+    )?;
 
-    let count_new_policy = count_valid_passwords(&passwords, validate_password_new_policy);
-    println!("Part 2: {count_new_policy}");
+    println!("Part 1: {}", counts.valid[0]);
+    println!("Part 2: {}", counts.valid[1]);
 
     Ok(())
 }
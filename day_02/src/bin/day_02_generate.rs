@@ -0,0 +1,24 @@
+use std::env;
+
+use day_02::{Generator, Rule};
+
+const USAGE: &str = "Usage: day_02_generate [--position] <seed> <lines> <valid>";
+
+fn main() -> Result<(), anyhow::Error> {
+    let (flags, positional): (Vec<_>, Vec<_>) =
+        env::args().skip(1).partition(|a| a.starts_with("--"));
+    let rule = match flags.as_slice() {
+        [] => Rule::Count,
+        [flag] if flag == "--position" => Rule::Position,
+        _ => anyhow::bail!(USAGE),
+    };
+
+    let [seed, lines, valid] = positional.as_slice() else {
+        anyhow::bail!(USAGE);
+    };
+
+    let problem = Generator::new(seed.parse()?).problem(lines.parse()?, valid.parse()?, rule)?;
+    print!("{problem}");
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use anyhow::bail;
+
+use crate::{PasswordWithPolicy, Problem};
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// SplitMix64, deterministic for a seed and good enough for test inputs
+#[derive(Clone, Debug)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-ish number in `low..=high`
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        let span = (high - low) as u64 + 1;
+        // span fits usize, so the remainder does too
+        low + usize::try_from(self.next_u64() % span).unwrap_or_default()
+    }
+
+    pub fn chance(&mut self, one_in: usize) -> bool {
+        self.range(1, one_in) == 1
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i));
+        }
+    }
+}
+
+/// Policy a generated password is valid or invalid under
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// `CountPolicy`
+    Count,
+    /// `PositionPolicy`, generated passwords are ASCII so every
+    /// `PositionMode` agrees
+    Position,
+}
+
+/// Generates passwords that pass or fail a `Rule` on purpose
+#[derive(Clone, Debug)]
+pub struct Generator {
+    rng: Rng,
+}
+
+impl Generator {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Generator {
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Random lowercase letter other than `letter`
+    fn filler(&mut self, letter: char) -> char {
+        loop {
+            let c = char::from(LETTERS[self.rng.range(0, LETTERS.len() - 1)]);
+
+            if c != letter {
+                return c;
+            }
+        }
+    }
+
+    /// Password for the letter and `min`/`max` of `template` that is valid
+    /// under `rule` iff `valid`, the password of `template` is ignored
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the letter of `template` is not ASCII or if no
+    /// password with the wanted outcome exists, e.g. a valid one under
+    /// `Rule::Count` for `min > max`
+    pub fn password(
+        &mut self,
+        template: &PasswordWithPolicy,
+        rule: Rule,
+        valid: bool,
+    ) -> Result<PasswordWithPolicy, anyhow::Error> {
+        let (letter, min, max) = (template.letter(), template.min(), template.max());
+
+        if !letter.is_ascii() {
+            bail!("can't generate passwords for non-ASCII letter {letter:?}");
+        }
+
+        let password = match rule {
+            Rule::Count => {
+                if valid && min > max {
+                    bail!("letter can't appear {min}-{max} times");
+                }
+
+                let count = if valid {
+                    self.rng.range(min, max)
+                } else if self.rng.chance(2) {
+                    self.rng.range(0, min - 1)
+                } else {
+                    self.rng.range(max + 1, max + 3)
+                };
+
+                let mut password: Vec<_> = (0..count + self.rng.range(0, 5))
+                    .map(|i| {
+                        if i < count {
+                            letter
+                        } else {
+                            self.filler(letter)
+                        }
+                    })
+                    .collect();
+                self.rng.shuffle(&mut password);
+                password
+            }
+            Rule::Position => {
+                if valid && min == max {
+                    bail!("letter can't be at exactly one of positions {min} and {max}");
+                }
+
+                let length = min.max(max) + self.rng.range(0, 3);
+                let mut password: Vec<_> = (0..length)
+                    .map(|_| {
+                        if self.rng.chance(4) {
+                            letter
+                        } else {
+                            self.filler(letter)
+                        }
+                    })
+                    .collect();
+
+                let (at_min, at_max) = match (valid, self.rng.chance(2)) {
+                    (true, first) => (first, !first),
+                    // equal positions can only hold the letter once
+                    (false, both) => (both && min != max, both && min != max),
+                };
+
+                for (position, at) in [(min, at_min), (max, at_max)] {
+                    password[position - 1] = if at { letter } else { self.filler(letter) };
+                }
+
+                password
+            }
+        };
+
+        PasswordWithPolicy::new(password.into_iter().collect(), letter, min, max)
+    }
+
+    /// Input of `lines` passwords with random policies, `valid` of them
+    /// valid under `rule`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `valid` is greater than `lines`
+    pub fn problem(
+        &mut self,
+        lines: usize,
+        valid: usize,
+        rule: Rule,
+    ) -> Result<Problem, anyhow::Error> {
+        if valid > lines {
+            bail!("can't have {valid} valid passwords out of {lines}");
+        }
+
+        let mut outcomes: Vec<_> = (0..lines).map(|i| i < valid).collect();
+        self.rng.shuffle(&mut outcomes);
+
+        let passwords = outcomes
+            .into_iter()
+            .map(|valid| {
+                let min = self.rng.range(1, 5);
+                let max = self.rng.range(min + 1, min + 6);
+                let letter = self.filler('\0');
+                let template = PasswordWithPolicy::new(String::new(), letter, min, max)?;
+
+                self.password(&template, rule, valid)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Problem { passwords })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_valid_passwords, CountPolicy, PasswordPolicy, PositionPolicy};
+
+    #[test]
+    fn test_generate_passwords() {
+        let policies: [(Rule, &dyn PasswordPolicy); 2] = [
+            (Rule::Count, &CountPolicy),
+            (Rule::Position, &PositionPolicy::default()),
+        ];
+        let mut generator = Generator::new(42);
+
+        for line in ["1-3 a: abcde", "2-9 c: ccccccccc", "4-4 z: zzzz"] {
+            let template: PasswordWithPolicy = line.parse().unwrap();
+
+            for (rule, policy) in policies {
+                for valid in [true, false] {
+                    let Ok(p) = generator.password(&template, rule, valid) else {
+                        assert!(valid && rule == Rule::Position && template.min() == 4);
+                        continue;
+                    };
+
+                    assert_eq!(policy.is_valid(&(&p).into()), valid, "{p} under {rule:?}");
+                }
+            }
+        }
+
+        let template: PasswordWithPolicy = "1-3 é: abc".parse().unwrap();
+        assert!(generator.password(&template, Rule::Position, true).is_err());
+    }
+
+    #[test]
+    fn test_generate_problem() {
+        for (rule, policy) in [
+            (Rule::Count, &CountPolicy as &dyn PasswordPolicy),
+            (Rule::Position, &PositionPolicy::default()),
+        ] {
+            let problem = Generator::new(7).problem(200, 123, rule).unwrap();
+
+            assert_eq!(problem.passwords.len(), 200);
+            assert_eq!(count_valid_passwords(&problem.passwords, policy), 123);
+            assert_eq!(problem.to_string().parse::<Problem>().unwrap(), problem);
+            assert_eq!(Generator::new(7).problem(200, 123, rule).unwrap(), problem);
+        }
+
+        assert!(Generator::new(7).problem(1, 2, Rule::Count).is_err());
+    }
+
+    #[test]
+    fn test_filler_covers_alphabet() {
+        let mut generator = Generator::new(1);
+        let mut seen: Vec<_> = (0..1000).map(|_| generator.filler('\0')).collect();
+        seen.sort_unstable();
+        seen.dedup();
+
+        assert_eq!(
+            seen.into_iter().collect::<String>(),
+            "abcdefghijklmnopqrstuvwxyz"
+        );
+    }
+}
@@ -1,16 +1,217 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-use parse_display::{Display, FromStr};
+use anyhow::{bail, Context};
+use parse_display::Display;
 
-#[derive(Debug, Display, FromStr)]
+mod generate;
+mod policy;
+mod stream;
+
+pub use generate::{Generator, Rule};
+pub use policy::{
+    CharClass, CharClassPolicy, CountPolicy, ForbiddenSubstringsPolicy, LengthPolicy,
+    PasswordPolicy, PositionMode, PositionPolicy,
+};
+pub use stream::{
+    count_valid_passwords_parallel, count_valid_passwords_streaming, PolicyCounts,
+    DEFAULT_CHUNK_SIZE,
+};
+
+#[derive(Debug, Display, PartialEq)]
 #[display("{min}-{max} {letter}: {password}")]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "PasswordFields")
+)]
 pub struct PasswordWithPolicy {
     password: String,
     letter: char,
     min: usize,
     max: usize,
+    /// Whether `password` is ASCII, so position checks don't scan it again
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ascii: bool,
+}
+
+/// Serialized `PasswordWithPolicy`, checked by `PasswordWithPolicy::new`
+/// when deserialized
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PasswordFields {
+    password: String,
+    letter: char,
+    min: usize,
+    max: usize,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PasswordFields> for PasswordWithPolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(f: PasswordFields) -> Result<Self, Self::Error> {
+        PasswordWithPolicy::new(f.password, f.letter, f.min, f.max)
+    }
+}
+
+impl PasswordWithPolicy {
+    /// # Errors
+    ///
+    /// Returns error if `min` or `max` is 0, positions are 1-based
+    pub fn new(
+        password: String,
+        letter: char,
+        min: usize,
+        max: usize,
+    ) -> Result<Self, anyhow::Error> {
+        check_positions(min, max)?;
+
+        Ok(PasswordWithPolicy {
+            ascii: password.is_ascii(),
+            password,
+            letter,
+            min,
+            max,
+        })
+    }
+
+    #[must_use]
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    #[must_use]
+    pub fn letter(&self) -> char {
+        self.letter
+    }
+
+    #[must_use]
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    #[must_use]
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl FromStr for PasswordWithPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PasswordRef::parse(s).map(Into::into)
+    }
+}
+
+/// `min` and `max` double as 1-based positions, so 0 is never valid
+fn check_positions(min: usize, max: usize) -> Result<(), anyhow::Error> {
+    if min == 0 || max == 0 {
+        bail!("positions are 1-based, got {min}-{max}");
+    }
+
+    Ok(())
+}
+
+/// `PasswordWithPolicy` borrowing the password from the line it was parsed
+/// from, policies are checked against this one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PasswordRef<'a> {
+    password: &'a str,
+    letter: char,
+    min: usize,
+    max: usize,
+    ascii: bool,
+}
+
+impl<'a> PasswordRef<'a> {
+    /// Parses `min-max letter: password` line
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the line is malformed or a position is 0
+    pub fn parse(line: &'a str) -> Result<Self, anyhow::Error> {
+        let (policy, password) = line.split_once(": ").context("missing ': '")?;
+        let (range, letter) = policy.split_once(' ').context("missing letter")?;
+        let (min, max) = range.split_once('-').context("missing '-'")?;
+
+        let mut letters = letter.chars();
+        let (Some(letter), None) = (letters.next(), letters.next()) else {
+            bail!("expected single letter, got {:?}", letter);
+        };
+
+        let (min, max) = (min.parse()?, max.parse()?);
+        check_positions(min, max)?;
+
+        Ok(PasswordRef {
+            password,
+            letter,
+            min,
+            max,
+            ascii: password.is_ascii(),
+        })
+    }
+
+    #[must_use]
+    pub fn password(&self) -> &'a str {
+        self.password
+    }
+
+    #[must_use]
+    pub fn letter(&self) -> char {
+        self.letter
+    }
+
+    #[must_use]
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    #[must_use]
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Whether the password is ASCII, found out once when parsing it
+    #[must_use]
+    pub fn is_ascii(&self) -> bool {
+        self.ascii
+    }
 }
 
+/// Parses line `number` (1-based) of an input, every way of reading
+/// passwords goes through this and `PasswordRef::parse`
+pub(crate) fn parse_line(line: &str, number: usize) -> Result<PasswordRef<'_>, anyhow::Error> {
+    PasswordRef::parse(line.trim_end_matches(['\n', '\r']))
+        .with_context(|| format!("malformed password on line {number}"))
+}
+
+impl<'a> From<&'a PasswordWithPolicy> for PasswordRef<'a> {
+    fn from(p: &'a PasswordWithPolicy) -> Self {
+        PasswordRef {
+            password: &p.password,
+            letter: p.letter,
+            min: p.min,
+            max: p.max,
+            ascii: p.ascii,
+        }
+    }
+}
+
+impl From<PasswordRef<'_>> for PasswordWithPolicy {
+    fn from(p: PasswordRef<'_>) -> Self {
+        PasswordWithPolicy {
+            password: p.password.to_string(),
+            letter: p.letter,
+            min: p.min,
+            max: p.max,
+            ascii: p.ascii,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     pub passwords: Vec<PasswordWithPolicy>,
 }
@@ -19,46 +220,108 @@ impl FromStr for Problem {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Problem {
-            passwords: s.lines().map(str::parse).collect::<Result<_, _>>()?,
-        })
+        let passwords = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_line(line, i + 1).map(PasswordWithPolicy::from))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Problem { passwords })
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for password in &self.passwords {
+            writeln!(f, "{password}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Same as `CountPolicy.is_valid`
 #[must_use]
 pub fn validate_password(p: &PasswordWithPolicy) -> bool {
-    let PasswordWithPolicy {
-        password, letter, ..
-    } = p;
-    let PasswordWithPolicy { min, max, .. } = *p;
-    let count = password.chars().filter(|c| c == letter).count();
-
-    min <= count && count <= max
+    CountPolicy.is_valid(&p.into())
 }
 
+/// Same as `PositionPolicy::default().is_valid`
 #[must_use]
 pub fn validate_password_new_policy(p: &PasswordWithPolicy) -> bool {
-    let PasswordWithPolicy { password, .. } = p;
-    let PasswordWithPolicy {
-        letter, min, max, ..
-    } = *p;
-
-    let Some(m) = password.chars().nth(min - 1) else {
-        return false;
-    };
-    let Some(n) = password.chars().nth(max - 1) else {
-        return false;
-    };
-
-    (m == letter || n == letter) && m != n
+    PositionPolicy::default().is_valid(&p.into())
 }
 
 #[must_use]
-pub fn count_valid_passwords<F>(passwords: &[PasswordWithPolicy], validation_fn: F) -> usize
+pub fn count_valid_passwords<P>(passwords: &[PasswordWithPolicy], policy: &P) -> usize
 where
-    F: Fn(&PasswordWithPolicy) -> bool,
+    P: PasswordPolicy + ?Sized,
 {
-    passwords.iter().filter(|p| validation_fn(p)).count()
+    passwords
+        .iter()
+        .filter(|&p| policy.is_valid(&p.into()))
+        .count()
+}
+
+/// Checks every password against every policy in one pass over
+/// `passwords`, returns numbers of valid passwords in the order of `policies`
+#[must_use]
+pub fn count_valid_passwords_by_policy(
+    passwords: &[PasswordWithPolicy],
+    policies: &[&dyn PasswordPolicy],
+) -> Vec<usize> {
+    let mut counts = vec![0; policies.len()];
+
+    for p in passwords {
+        let p = PasswordRef::from(p);
+
+        for (count, policy) in counts.iter_mut().zip(policies) {
+            if policy.is_valid(&p) {
+                *count += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+/// Password that breaks a policy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// 1-based line number of the password in the input
+    pub line: usize,
+    pub policy: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.policy, self.reason)
+    }
+}
+
+/// Checks every password against every policy, returns violations ordered
+/// by line and then by the order of `policies`
+#[must_use]
+pub fn find_violations(
+    passwords: &[PasswordWithPolicy],
+    policies: &[&dyn PasswordPolicy],
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (i, p) in passwords.iter().enumerate() {
+        for policy in policies {
+            if let Some(reason) = policy.violation(&p.into()) {
+                violations.push(Violation {
+                    line: i + 1,
+                    policy: policy.name().to_string(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    violations
 }
 
 #[cfg(test)]
@@ -74,7 +337,11 @@ mod tests {
     fn test_count_valid_passwords_with_old_policy() {
         let Problem { passwords } = TEST_INPUT.parse().unwrap();
 
-        assert_eq!(count_valid_passwords(&passwords, validate_password), 2);
+        assert_eq!(count_valid_passwords(&passwords, &CountPolicy), 2);
+        assert_eq!(
+            passwords.iter().map(validate_password).collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
     }
 
     #[test]
@@ -82,8 +349,179 @@ mod tests {
         let Problem { passwords } = TEST_INPUT.parse().unwrap();
 
         assert_eq!(
-            count_valid_passwords(&passwords, validate_password_new_policy),
+            count_valid_passwords(&passwords, &PositionPolicy::default()),
             1
         );
+        assert_eq!(
+            passwords
+                .iter()
+                .map(validate_password_new_policy)
+                .collect::<Vec<_>>(),
+            vec![true, false, false]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+
+        assert!(
+            json.starts_with(r#"{"passwords":[{"password":"abcde","letter":"a","min":1,"max":3}"#)
+        );
+
+        let Problem { passwords } = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(count_valid_passwords(&passwords, &CountPolicy), 2);
+        assert!(serde_json::from_str::<PasswordWithPolicy>(
+            r#"{"password":"abc","letter":"a","min":0,"max":3}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_count_valid_passwords_by_policy() {
+        let Problem { passwords } = TEST_INPUT.parse().unwrap();
+        let length = LengthPolicy { min: 6, max: 20 };
+        let classes = CharClassPolicy {
+            required: vec![CharClass::Lowercase],
+        };
+        let forbidden = ForbiddenSubstringsPolicy {
+            forbidden: vec!["cde".to_string()],
+        };
+        let policies: [&dyn PasswordPolicy; 5] = [
+            &CountPolicy,
+            &PositionPolicy::default(),
+            &length,
+            &classes,
+            &forbidden,
+        ];
+
+        assert_eq!(
+            count_valid_passwords_by_policy(&passwords, &policies),
+            vec![2, 1, 1, 3, 1]
+        );
+        assert_eq!(
+            policies.map(|p| p.name()),
+            [
+                "count",
+                "position",
+                "length",
+                "character classes",
+                "forbidden substrings"
+            ]
+        );
+
+        for p in &passwords {
+            for policy in policies {
+                assert_eq!(
+                    policy.is_valid(&p.into()),
+                    policy.violation(&p.into()).is_none()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_violations() {
+        let Problem { passwords } = TEST_INPUT.parse().unwrap();
+        let violations = find_violations(&passwords, &[&CountPolicy, &PositionPolicy::default()]);
+
+        assert_eq!(
+            violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "line 2: count: letter 'b' appears 0 times, expected 1-3",
+                "line 2: position: neither position 1 nor 3 contains 'b'",
+                "line 3: position: positions 2 and 9 both contain 'c'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_char_classes() {
+        let password = PasswordWithPolicy::new("aB3$".to_string(), 'a', 1, 1).unwrap();
+        let all = CharClassPolicy {
+            required: vec![
+                CharClass::Lowercase,
+                CharClass::Uppercase,
+                CharClass::Digit,
+                CharClass::Symbol,
+            ],
+        };
+
+        assert!(all.is_valid(&PasswordRef::from(&password)));
+        assert_eq!(
+            all.violation(&PasswordRef::parse("1-1 a: ab3").unwrap()),
+            Some("no uppercase or symbol character".to_string())
+        );
+        assert_eq!(password.to_string(), "1-1 a: aB3$");
+    }
+
+    #[test]
+    fn test_parse_password_ref() {
+        for line in TEST_INPUT.lines() {
+            let owned: PasswordWithPolicy = line.parse().unwrap();
+
+            assert_eq!(PasswordRef::parse(line).unwrap(), PasswordRef::from(&owned));
+        }
+
+        assert!(PasswordRef::parse("1-3 ab: abc").is_err());
+        assert!(PasswordRef::parse("1-3 a abc").is_err());
+        assert!(PasswordRef::parse("1 a: abc").is_err());
+
+        assert!(PasswordRef::parse("0-3 a: abc").is_err());
+        assert_eq!(
+            format!("{TEST_INPUT}\n1-3 a abc")
+                .parse::<Problem>()
+                .unwrap_err()
+                .to_string(),
+            "malformed password on line 4"
+        );
+        assert!("1-0 a: abc".parse::<PasswordWithPolicy>().is_err());
+        assert!(PasswordWithPolicy::new("abc".to_string(), 'a', 0, 3).is_err());
+    }
+
+    #[test]
+    fn test_position_modes() {
+        // 'e' followed by a combining acute accent is 3 bytes, 2 chars and
+        // 1 grapheme
+        let p = PasswordRef::parse("2-4 x: e\u{301}xe").unwrap();
+        let violation = |mode| PositionPolicy::new(mode).violation(&p);
+
+        assert_eq!(violation(PositionMode::Bytes), None);
+        assert_eq!(
+            violation(PositionMode::Chars).unwrap(),
+            "neither position 2 nor 4 contains 'x'"
+        );
+        assert_eq!(
+            violation(PositionMode::Graphemes).unwrap(),
+            "position 4 is outside of the password"
+        );
+
+        let p = PasswordRef::parse("3-2 é: aéb").unwrap();
+
+        assert_eq!(PositionPolicy::new(PositionMode::Bytes).violation(&p), None);
+        assert_eq!(PositionPolicy::new(PositionMode::Chars).violation(&p), None);
+
+        // only unvalidated input gets position 0 this far
+        let p = PasswordRef { min: 0, ..p };
+
+        assert_eq!(
+            PositionPolicy::default().violation(&p).unwrap(),
+            "position 0 is outside of the password"
+        );
+        assert!(!PositionPolicy::new(PositionMode::Bytes).is_valid(&p));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
     }
 }
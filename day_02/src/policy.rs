@@ -0,0 +1,302 @@
+use std::fmt;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::PasswordRef;
+
+/// Rule a password has to follow, implementations can use the policy each
+/// line of the input comes with or ignore it and bring their own
+pub trait PasswordPolicy: Sync {
+    /// Short name used when reporting results of several policies
+    fn name(&self) -> &str;
+
+    /// Explains why `p` breaks the policy, `None` if it doesn't
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String>;
+
+    /// Counting calls this for every line, implementations should override
+    /// it so that invalid passwords don't format a `violation`
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        self.violation(p).is_none()
+    }
+}
+
+impl<P: PasswordPolicy + ?Sized> PasswordPolicy for &P {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String> {
+        (**self).violation(p)
+    }
+
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        (**self).is_valid(p)
+    }
+}
+
+/// Letter of the line occurs between `min` and `max` times (part 1)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CountPolicy;
+
+impl CountPolicy {
+    fn count(p: &PasswordRef<'_>) -> usize {
+        p.password().chars().filter(|&c| c == p.letter()).count()
+    }
+}
+
+impl PasswordPolicy for CountPolicy {
+    fn name(&self) -> &str {
+        "count"
+    }
+
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String> {
+        let count = CountPolicy::count(p);
+
+        (count < p.min() || p.max() < count).then(|| {
+            format!(
+                "letter '{}' appears {} times, expected {}-{}",
+                p.letter(),
+                count,
+                p.min(),
+                p.max()
+            )
+        })
+    }
+
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        (p.min()..=p.max()).contains(&CountPolicy::count(p))
+    }
+}
+
+/// What the 1-based positions of `PositionPolicy` count
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionMode {
+    /// Bytes of the UTF-8 encoding, the letter has to start at the position
+    Bytes,
+    /// Unicode scalar values
+    #[default]
+    Chars,
+    /// Extended grapheme clusters, the letter has to be a whole cluster
+    Graphemes,
+}
+
+impl PositionMode {
+    /// Whether the letter of `p` is at 0-based indices `i` and `j` of its
+    /// password, `None` for an index outside of it. Bytes and ASCII
+    /// passwords, where bytes, chars and graphemes are the same, are looked
+    /// up in O(1), the rest in a single pass.
+    fn letter_at(self, p: &PasswordRef<'_>, i: usize, j: usize) -> [Option<bool>; 2] {
+        let (password, letter) = (p.password(), p.letter());
+        let mut buf = [0; 4];
+        let encoded = letter.encode_utf8(&mut buf);
+        let at_byte = |index: usize| {
+            password
+                .as_bytes()
+                .get(index..)
+                .filter(|rest| !rest.is_empty())
+                .map(|rest| rest.starts_with(encoded.as_bytes()))
+        };
+
+        if self == PositionMode::Bytes || p.is_ascii() {
+            return [at_byte(i), at_byte(j)];
+        }
+
+        match self {
+            PositionMode::Chars => two_at(password.chars(), i, j).map(|c| c.map(|c| c == letter)),
+            _ => two_at(password.graphemes(true), i, j).map(|g| g.map(|g| g == encoded)),
+        }
+    }
+}
+
+/// Items at indices `i` and `j` of `iter`, found in one pass
+fn two_at<I>(iter: I, i: usize, j: usize) -> [Option<I::Item>; 2]
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    let (low, high) = (i.min(j), i.max(j));
+    let mut iter = iter.skip(low);
+    let first = iter.next();
+    let second = if high == low {
+        first.clone()
+    } else {
+        iter.nth(high - low - 1)
+    };
+
+    if i <= j {
+        [first, second]
+    } else {
+        [second, first]
+    }
+}
+
+/// Letter of the line is at exactly one of the 1-based positions `min` and
+/// `max` (part 2)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionPolicy {
+    pub mode: PositionMode,
+}
+
+impl PositionPolicy {
+    #[must_use]
+    pub fn new(mode: PositionMode) -> Self {
+        PositionPolicy { mode }
+    }
+
+    fn letter_at(self, p: &PasswordRef<'_>) -> [Option<bool>; 2] {
+        // parsing rejects position 0, were it to get here anyway it's
+        // outside of the password like positions past its end
+        let index = |position: usize| position.checked_sub(1).unwrap_or(usize::MAX);
+
+        self.mode.letter_at(p, index(p.min()), index(p.max()))
+    }
+}
+
+impl PasswordPolicy for PositionPolicy {
+    fn name(&self) -> &str {
+        "position"
+    }
+
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String> {
+        let (first, second) = (p.min(), p.max());
+
+        match self.letter_at(p) {
+            [None, _] => Some(format!("position {first} is outside of the password")),
+            [_, None] => Some(format!("position {second} is outside of the password")),
+            [Some(true), Some(true)] => Some(format!(
+                "positions {} and {} both contain '{}'",
+                first,
+                second,
+                p.letter()
+            )),
+            [Some(false), Some(false)] => Some(format!(
+                "neither position {} nor {} contains '{}'",
+                first,
+                second,
+                p.letter()
+            )),
+            _ => None,
+        }
+    }
+
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        matches!(self.letter_at(p), [Some(m), Some(n)] if m != n)
+    }
+}
+
+/// Password has between `min` and `max` characters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthPolicy {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl PasswordPolicy for LengthPolicy {
+    fn name(&self) -> &str {
+        "length"
+    }
+
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String> {
+        let length = p.password().chars().count();
+
+        (length < self.min || self.max < length).then(|| {
+            format!(
+                "password has {} characters, expected {}-{}",
+                length, self.min, self.max
+            )
+        })
+    }
+
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        (self.min..=self.max).contains(&p.password().chars().count())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    /// Anything that isn't alphanumeric
+    Symbol,
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CharClass::Lowercase => "lowercase",
+            CharClass::Uppercase => "uppercase",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl CharClass {
+    #[must_use]
+    pub fn contains(self, c: char) -> bool {
+        match self {
+            CharClass::Lowercase => c.is_lowercase(),
+            CharClass::Uppercase => c.is_uppercase(),
+            CharClass::Digit => c.is_numeric(),
+            CharClass::Symbol => !c.is_alphanumeric(),
+        }
+    }
+}
+
+/// Password has at least one character of every class
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharClassPolicy {
+    pub required: Vec<CharClass>,
+}
+
+impl PasswordPolicy for CharClassPolicy {
+    fn name(&self) -> &str {
+        "character classes"
+    }
+
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String> {
+        let missing: Vec<_> = self
+            .required
+            .iter()
+            .filter(|class| !p.password().chars().any(|c| class.contains(c)))
+            .map(ToString::to_string)
+            .collect();
+
+        (!missing.is_empty()).then(|| format!("no {} character", missing.join(" or ")))
+    }
+
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        self.required
+            .iter()
+            .all(|class| p.password().chars().any(|c| class.contains(c)))
+    }
+}
+
+/// Password contains none of the substrings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForbiddenSubstringsPolicy {
+    pub forbidden: Vec<String>,
+}
+
+impl PasswordPolicy for ForbiddenSubstringsPolicy {
+    fn name(&self) -> &str {
+        "forbidden substrings"
+    }
+
+    fn violation(&self, p: &PasswordRef<'_>) -> Option<String> {
+        self.forbidden
+            .iter()
+            .find(|s| p.password().contains(s.as_str()))
+            .map(|s| format!("contains forbidden \"{s}\""))
+    }
+
+    fn is_valid(&self, p: &PasswordRef<'_>) -> bool {
+        !self
+            .forbidden
+            .iter()
+            .any(|s| p.password().contains(s.as_str()))
+    }
+}
@@ -0,0 +1,196 @@
+use std::{io::BufRead, thread};
+
+use crate::{parse_line, PasswordPolicy, PasswordRef};
+
+/// Default number of bytes a worker of `count_valid_passwords_parallel`
+/// gets at once
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// Numbers of passwords valid under every policy, in the order policies were
+/// given in
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolicyCounts {
+    pub total: usize,
+    pub valid: Vec<usize>,
+}
+
+impl PolicyCounts {
+    fn new(policies: usize) -> Self {
+        PolicyCounts {
+            total: 0,
+            valid: vec![0; policies],
+        }
+    }
+
+    fn add(&mut self, p: &PasswordRef<'_>, policies: &[&dyn PasswordPolicy]) {
+        self.total += 1;
+
+        for (count, policy) in self.valid.iter_mut().zip(policies) {
+            if policy.is_valid(p) {
+                *count += 1;
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &PolicyCounts) {
+        self.total += other.total;
+
+        for (count, other) in self.valid.iter_mut().zip(&other.valid) {
+            *count += other;
+        }
+    }
+}
+
+/// Validates passwords line by line, only one line is kept in memory at a
+/// time
+///
+/// # Errors
+///
+/// Returns error if reading fails or a line is malformed
+pub fn count_valid_passwords_streaming<R: BufRead>(
+    mut reader: R,
+    policies: &[&dyn PasswordPolicy],
+) -> Result<PolicyCounts, anyhow::Error> {
+    let mut counts = PolicyCounts::new(policies.len());
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? != 0 {
+        counts.add(&parse_line(&line, counts.total + 1)?, policies);
+        line.clear();
+    }
+
+    Ok(counts)
+}
+
+/// Appends whole lines to `chunk` until it holds at least `chunk_size` bytes
+/// or the input ends, returns number of lines read
+fn read_chunk<R: BufRead>(
+    reader: &mut R,
+    chunk: &mut String,
+    chunk_size: usize,
+) -> Result<usize, anyhow::Error> {
+    let mut lines = 0;
+
+    while chunk.len() < chunk_size && reader.read_line(chunk)? != 0 {
+        lines += 1;
+    }
+
+    Ok(lines)
+}
+
+fn count_chunk(
+    chunk: &str,
+    first_line: usize,
+    policies: &[&dyn PasswordPolicy],
+) -> Result<PolicyCounts, anyhow::Error> {
+    let mut counts = PolicyCounts::new(policies.len());
+
+    for (i, line) in chunk.lines().enumerate() {
+        counts.add(&parse_line(line, first_line + i)?, policies);
+    }
+
+    Ok(counts)
+}
+
+/// Reads `threads` chunks of about `chunk_size` bytes, validates them in
+/// parallel and repeats until the input ends, so at most
+/// `threads * chunk_size` bytes (plus a line) are in memory at a time
+///
+/// # Errors
+///
+/// Returns error if reading fails or a line is malformed
+pub fn count_valid_passwords_parallel<R: BufRead>(
+    mut reader: R,
+    policies: &[&dyn PasswordPolicy],
+    threads: usize,
+    chunk_size: usize,
+) -> Result<PolicyCounts, anyhow::Error> {
+    let mut counts = PolicyCounts::new(policies.len());
+    let mut chunks = vec![String::new(); threads.max(1)];
+    let mut first_lines = vec![0; chunks.len()];
+    let mut next_line = 1;
+
+    loop {
+        for (chunk, first_line) in chunks.iter_mut().zip(&mut first_lines) {
+            chunk.clear();
+            *first_line = next_line;
+            next_line += read_chunk(&mut reader, chunk, chunk_size)?;
+        }
+
+        if chunks.iter().all(String::is_empty) {
+            return Ok(counts);
+        }
+
+        let results = thread::scope(|s| {
+            let workers: Vec<_> = chunks
+                .iter()
+                .zip(&first_lines)
+                .map(|(chunk, &first_line)| {
+                    s.spawn(move || count_chunk(chunk, first_line, policies))
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|w| w.join().expect("password validation worker panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        for result in results {
+            counts.merge(&result?);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CountPolicy, PositionPolicy};
+
+    const TEST_INPUT: &str = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc
+";
+
+    #[test]
+    fn test_count_valid_passwords_streaming() {
+        let counts = count_valid_passwords_streaming(
+            TEST_INPUT.as_bytes(),
+            &[&CountPolicy, &PositionPolicy::default()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            counts,
+            PolicyCounts {
+                total: 3,
+                valid: vec![2, 1]
+            }
+        );
+    }
+
+    #[test]
+    fn test_count_valid_passwords_parallel() {
+        let input = TEST_INPUT.repeat(100);
+
+        for (threads, chunk_size) in [(1, 1), (3, 1), (4, 50), (2, DEFAULT_CHUNK_SIZE)] {
+            let counts = count_valid_passwords_parallel(
+                input.as_bytes(),
+                &[&CountPolicy, &PositionPolicy::default()],
+                threads,
+                chunk_size,
+            )
+            .unwrap();
+
+            assert_eq!(counts.total, 300);
+            assert_eq!(counts.valid, vec![200, 100]);
+        }
+
+        let malformed = format!("{TEST_INPUT}1-3 a abcde\n");
+        let error = count_valid_passwords_parallel(malformed.as_bytes(), &[&CountPolicy], 2, 1)
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "malformed password on line 4");
+    }
+}
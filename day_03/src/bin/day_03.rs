@@ -1,21 +1,186 @@
-use std::{env, fs};
+use std::{
+    env,
+    fs::{self, File},
+    io::BufWriter,
+};
 
-use anyhow::Context;
-use day_03::{count_collisions, Problem};
+use anyhow::{bail, Context};
+use common::Config;
+use day_03::{
+    count_collisions, count_collisions_for_rational_slopes, product_of_collisions, render,
+    scan_slopes, write_ppm, Overlay, Path, Problem, RationalSlope, Slope, PART_ONE_SLOPE,
+    PART_TWO_SLOPES,
+};
+
+/// Number of slopes printed from each end of the ranking
+const RANKED_SHOWN: usize = 5;
+
+/// Command line arguments of the day 3 binary
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Args {
+    /// Slopes to multiply in part 2, `PART_TWO_SLOPES` if empty
+    slopes: Vec<Slope>,
+    /// Lines to count collisions of, given as `right/down`
+    lines: Vec<RationalSlope>,
+    /// Rank slopes up to this one instead of solving the puzzle
+    rank: Option<Slope>,
+    /// Draw the paths of the slopes and lines, the part 1 slope if there are
+    /// none
+    render: bool,
+    /// Draw the paths into this PPM image instead of printing them
+    ppm: Option<String>,
+    input: Option<String>,
+}
+
+impl Args {
+    /// # Errors
+    ///
+    /// Returns error on unknown flags, malformed slopes or lines, or too
+    /// many arguments
+    fn parse(args: Vec<String>) -> Result<Args, anyhow::Error> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--rank" => {
+                    let max = args.next().context("--rank needs the largest slope")?;
+                    parsed.rank = Some(max.parse()?);
+                }
+                "--render" => parsed.render = true,
+                "--ppm" => {
+                    parsed.render = true;
+                    parsed.ppm = Some(args.next().context("--ppm needs the image path")?);
+                }
+                flag if flag.starts_with("--") => bail!("unknown flag {}", flag),
+                _ if parsed.input.is_some() => bail!("unexpected argument {}", arg),
+                _ if is_pair(&arg, ',') => parsed.slopes.push(
+                    arg.parse()
+                        .with_context(|| format!("invalid slope {arg}"))?,
+                ),
+                _ if is_pair(&arg, '/') => parsed
+                    .lines
+                    .push(arg.parse().with_context(|| format!("invalid line {arg}"))?),
+                _ => parsed.input = Some(arg),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Whether `arg` is two numbers joined by `separator`, so it's a slope or a
+/// line rather than the input path
+fn is_pair(arg: &str, separator: char) -> bool {
+    arg.split_once(separator).is_some_and(|(a, b)| {
+        [a, b]
+            .iter()
+            .all(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let args = Args::parse(args)?;
+    let input_path = config.input_path(3, args.input.as_deref());
     let content = fs::read_to_string(input_path)?;
     let p: Problem = content.parse()?;
 
-    let count = count_collisions(&p, 3, 1);
+    if let Some(max) = args.rank {
+        let ranked = scan_slopes(&p, max.right, max.down);
+        let shown = RANKED_SHOWN.min(ranked.len());
+
+        println!("Fewest trees:");
+        for s in &ranked[..shown] {
+            println!("  {}: {}", s.slope, s.hits);
+        }
+
+        println!("Most trees:");
+        for s in ranked.iter().rev().take(shown) {
+            println!("  {}: {}", s.slope, s.hits);
+        }
+
+        return Ok(());
+    }
+
+    if args.render {
+        let overlays: Vec<_> = if args.slopes.is_empty() && args.lines.is_empty() {
+            vec![Overlay::new(PART_ONE_SLOPE)]
+        } else {
+            let slopes = args.slopes.iter().map(|&slope| Path::from(slope));
+            let lines = args.lines.iter().map(|&line| Path::from(line));
+
+            slopes
+                .chain(lines)
+                .enumerate()
+                .map(|(i, path)| Overlay::nth(path, i))
+                .collect()
+        };
+
+        match args.ppm {
+            Some(path) => write_ppm(&p, &overlays, BufWriter::new(File::create(path)?))?,
+            None => print!("{}", render(&p, &overlays)),
+        }
+
+        return Ok(());
+    }
+
+    let count = count_collisions(&p, PART_ONE_SLOPE.right, PART_ONE_SLOPE.down);
     println!("Part 1: {count}");
 
-    let result: usize = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
-        .map(|(right, down)| count_collisions(&p, right, down))
-        .iter()
-        .product();
+    let slopes = if args.slopes.is_empty() {
+        &PART_TWO_SLOPES[..]
+    } else {
+        &args.slopes
+    };
+    let result = product_of_collisions(&p, slopes)?;
     println!("Part 2: {result}");
 
+    for (line, hits) in args
+        .lines
+        .iter()
+        .zip(count_collisions_for_rational_slopes(&p, &args.lines))
+    {
+        println!("Line {line}: {hits}");
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let args = |args: &[&str]| Args::parse(args.iter().map(ToString::to_string).collect());
+
+        assert_eq!(
+            args(&["1,2", "3,1", "input.txt"]).unwrap(),
+            Args {
+                slopes: vec![Slope { right: 1, down: 2 }, Slope { right: 3, down: 1 }],
+                lines: Vec::new(),
+                rank: None,
+                render: false,
+                ppm: None,
+                input: Some("input.txt".to_string()),
+            }
+        );
+        assert_eq!(
+            args(&["--rank", "30,2"]).unwrap().rank,
+            Some(Slope { right: 30, down: 2 })
+        );
+        assert!(args(&["--rank", "3,0"]).is_err());
+        assert!(args(&["input.txt", "1,1"]).is_err());
+        assert!(args(&["--slope"]).is_err());
+        assert!(args(&["--ppm"]).is_err());
+        assert_eq!(
+            args(&["2/3", "3,1", "dir/input.txt"]).unwrap().lines,
+            vec![RationalSlope { right: 2, down: 3 }]
+        );
+        assert!(args(&["2/0"]).is_err());
+        assert!(args(&["3,0"]).is_err());
+        assert_eq!(args(&["a/b"]).unwrap().input.as_deref(), Some("a/b"));
+        assert!(args(&["--ppm", "map.ppm"]).unwrap().render);
+    }
+}
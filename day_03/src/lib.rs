@@ -1,46 +1,306 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-#[derive(Debug)]
+use anyhow::Context;
+use common::{checked_product, OverflowError};
+
+mod line;
+mod render;
+
+pub use line::{count_collisions_for_rational_slopes, count_collisions_rational, RationalSlope};
+pub use render::{render, write_ppm, Overlay, Path, Rgb};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Map of trees, one bit per cell. Every row starts at a new word so a row is
+/// a contiguous run of `words_per_row` words.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "MapFields", try_from = "MapFields")
+)]
 pub struct Problem {
+    trees: Vec<u64>,
+    width: usize,
+    height: usize,
+}
+
+/// Serialized `Problem`, one flag per cell row by row like before the map
+/// was bit-packed, checked when deserialized
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MapFields {
     map: Vec<bool>,
     width: usize,
 }
 
+#[cfg(feature = "serde")]
+impl From<Problem> for MapFields {
+    fn from(p: Problem) -> Self {
+        let map = (0..p.height)
+            .flat_map(|y| (0..p.width).map(move |x| (x, y)))
+            .map(|(x, y)| p.is_tree(x, y))
+            .collect();
+
+        MapFields {
+            map,
+            width: p.width,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MapFields> for Problem {
+    type Error = anyhow::Error;
+
+    fn try_from(f: MapFields) -> Result<Self, Self::Error> {
+        if f.width == 0 || f.map.is_empty() {
+            anyhow::bail!("map is empty");
+        }
+
+        #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+        if f.map.len() % f.width != 0 {
+            anyhow::bail!("{} cells don't fill rows of {} cells", f.map.len(), f.width);
+        }
+
+        let words_per_row = f.width.div_ceil(WORD_BITS);
+        let mut trees = Vec::new();
+
+        for row in f.map.chunks(f.width) {
+            let mut words = vec![0; words_per_row];
+
+            for (x, _) in row.iter().enumerate().filter(|(_, &tree)| tree) {
+                words[x / WORD_BITS] |= 1 << (x % WORD_BITS);
+            }
+
+            trees.extend(words);
+        }
+
+        Ok(Problem {
+            trees,
+            width: f.width,
+            height: f.map.len() / f.width,
+        })
+    }
+}
+
 impl FromStr for Problem {
     type Err = anyhow::Error;
 
+    /// Parses rows of `.` for open cells and `#` for trees, all of the same
+    /// length
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, |l| l.chars().count());
+
+        if width == 0 {
+            anyhow::bail!("map is empty");
+        }
+
+        let words_per_row = width.div_ceil(WORD_BITS);
+        let mut trees = Vec::new();
+        let mut height = 0;
+
+        for (y, line) in s.lines().enumerate() {
+            let mut row = vec![0; words_per_row];
+            let mut cells = 0;
+
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '.' => {}
+                    '#' if x < width => row[x / WORD_BITS] |= 1 << (x % WORD_BITS),
+                    // row is too long, reported below
+                    '#' => {}
+                    _ => anyhow::bail!(
+                        "line {}, column {}: expected '.' or '#', got {:?}",
+                        y + 1,
+                        x + 1,
+                        c
+                    ),
+                }
+
+                cells += 1;
+            }
+
+            if cells != width {
+                anyhow::bail!(
+                    "line {}: expected {} cells like the first line, got {}",
+                    y + 1,
+                    width,
+                    cells
+                );
+            }
+
+            trees.extend(row);
+            height += 1;
+        }
+
         Ok(Problem {
-            map: s
-                .lines()
-                .flat_map(|l| l.chars().map(|c| c == '#'))
-                .collect(),
-            width: s.lines().nth(0).unwrap().len(),
+            trees,
+            width,
+            height,
         })
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            let row: String = (0..self.width)
+                .map(|x| if self.is_tree(x, y) { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{row}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Problem {
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether there's a tree at column `x` of row `y`, the map repeats to
+    /// the right
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is outside of the map
+    #[must_use]
+    pub fn is_tree(&self, x: usize, y: usize) -> bool {
+        assert!(y < self.height, "row {y} is outside of the map");
+
+        let x = x % self.width;
+        let word = self.trees[y * self.width.div_ceil(WORD_BITS) + x / WORD_BITS];
+
+        word >> (x % WORD_BITS) & 1 == 1
+    }
+}
+
+/// Slope of part 1
+pub const PART_ONE_SLOPE: Slope = Slope { right: 3, down: 1 };
+
+/// Slopes whose collisions are multiplied in part 2
+pub const PART_TWO_SLOPES: [Slope; 5] = [
+    Slope { right: 1, down: 1 },
+    Slope { right: 3, down: 1 },
+    Slope { right: 5, down: 1 },
+    Slope { right: 7, down: 1 },
+    Slope { right: 1, down: 2 },
+];
+
+/// Step of the toboggan, `down` is at least 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Slope {
+    pub right: usize,
+    pub down: usize,
+}
+
+impl Slope {
+    /// Column the toboggan is at in row `y`, `None` if it skips the row
+    fn column(self, y: usize, width: usize) -> Option<usize> {
+        #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+        let on_row = y % self.down == 0;
+
+        on_row.then(|| (y / self.down % width) * (self.right % width))
+    }
+}
+
+impl FromStr for Slope {
+    type Err = anyhow::Error;
+
+    /// Parses `right,down`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right, down) = s.split_once(',').context("expected right,down")?;
+        let slope = Slope {
+            right: right.parse()?,
+            down: down.parse()?,
+        };
+
+        if slope.down == 0 {
+            anyhow::bail!("slope has to go down");
+        }
+
+        Ok(slope)
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+/// # Panics
+///
+/// Panics if `down` is 0
 #[must_use]
 pub fn count_collisions(p: &Problem, right: usize, down: usize) -> usize {
-    let Problem { map, width } = p;
-    let mut dx = 0;
-    let mut dy = 0;
-    let mut i = 0;
-    let mut hits = 0;
-
-    while i < map.len() {
-        if map[i] {
-            hits += 1;
-        }
+    count_collisions_for_slopes(p, &[Slope { right, down }])[0]
+}
+
+/// Counts collisions of every slope in one walk over the rows of the map
+///
+/// # Panics
+///
+/// Panics if a slope doesn't go down
+#[must_use]
+pub fn count_collisions_for_slopes(p: &Problem, slopes: &[Slope]) -> Vec<usize> {
+    assert!(slopes.iter().all(|s| s.down > 0), "slope has to go down");
 
-        dx = (dx + right) % width;
-        dy += width * down;
-        i = dx + dy;
+    let mut hits = vec![0; slopes.len()];
+
+    for y in 0..p.height() {
+        for (hits, slope) in hits.iter_mut().zip(slopes) {
+            if slope.column(y, p.width()).is_some_and(|x| p.is_tree(x, y)) {
+                *hits += 1;
+            }
+        }
     }
 
     hits
 }
 
+/// Product of collisions of all `slopes`
+///
+/// # Errors
+///
+/// Returns error if the product overflows
+pub fn product_of_collisions(p: &Problem, slopes: &[Slope]) -> Result<usize, OverflowError> {
+    checked_product(count_collisions_for_slopes(p, slopes))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlopeHits {
+    pub slope: Slope,
+    pub hits: usize,
+}
+
+/// Counts collisions of every slope going up to `max_right` right and
+/// `max_down` down, ranked from the fewest to the most trees hit. Ties are
+/// ordered by slope.
+#[must_use]
+pub fn scan_slopes(p: &Problem, max_right: usize, max_down: usize) -> Vec<SlopeHits> {
+    let slopes: Vec<_> = (1..=max_down)
+        .flat_map(|down| (0..=max_right).map(move |right| Slope { right, down }))
+        .collect();
+
+    let mut ranked: Vec<_> = slopes
+        .iter()
+        .zip(count_collisions_for_slopes(p, &slopes))
+        .map(|(&slope, hits)| SlopeHits { slope, hits })
+        .collect();
+    ranked.sort_unstable_by_key(|s| (s.hits, s.slope));
+
+    ranked
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +328,101 @@ mod tests {
         assert_eq!(count_collisions(&p, 7, 1), 4);
         assert_eq!(count_collisions(&p, 1, 2), 2);
     }
+
+    #[test]
+    fn test_count_collisions_for_slopes() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(
+            count_collisions_for_slopes(&p, &PART_TWO_SLOPES),
+            vec![2, 7, 3, 4, 2]
+        );
+        assert_eq!(product_of_collisions(&p, &PART_TWO_SLOPES), Ok(336));
+        assert_eq!(product_of_collisions(&p, &[]), Ok(1));
+        assert!(p.is_tree(2, 0) && p.is_tree(13, 0) && !p.is_tree(0, 0));
+    }
+
+    #[test]
+    fn test_scan_slopes() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let ranked = scan_slopes(&p, 10, 2);
+
+        assert_eq!(ranked.len(), 22);
+        assert!(ranked.windows(2).all(|w| w[0].hits <= w[1].hits));
+
+        for s in &ranked {
+            assert_eq!(count_collisions(&p, s.slope.right, s.slope.down), s.hits);
+        }
+
+        assert_eq!(
+            ranked.last(),
+            Some(&SlopeHits {
+                slope: Slope { right: 3, down: 1 },
+                hits: 7
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |s: &str| s.parse::<Problem>().unwrap_err().to_string();
+
+        assert_eq!(error(""), "map is empty");
+        assert_eq!(
+            error("..#\n.#\n..."),
+            "line 2: expected 3 cells like the first line, got 2"
+        );
+        assert_eq!(
+            error("..#\n.#.#\n..."),
+            "line 2: expected 3 cells like the first line, got 4"
+        );
+        assert_eq!(
+            error("..#\n...\n.O."),
+            "line 3, column 2: expected '.' or '#', got 'O'"
+        );
+    }
+
+    #[test]
+    fn test_wide_map() {
+        // rows wider than a word are packed into several
+        let row = |tree: usize| -> String {
+            (0..130)
+                .map(|x| if x == tree { '#' } else { '.' })
+                .collect()
+        };
+        let input = [row(0), row(64), row(129), row(63)].join("\n");
+        let p: Problem = input.parse().unwrap();
+
+        assert_eq!(p.width(), 130);
+        assert_eq!(p.height(), 4);
+        assert!(p.is_tree(130, 0) && p.is_tree(64, 1) && p.is_tree(129, 2) && p.is_tree(63, 3));
+        assert_eq!(p.to_string(), format!("{input}\n"));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+
+        assert!(json.starts_with(r#"{"map":[false,false,true,true,false"#));
+        assert!(json.ends_with(r#"false,true],"width":11}"#));
+        assert_eq!(serde_json::from_str::<Problem>(&json).unwrap(), p);
+
+        for invalid in [
+            r#"{"map":[],"width":0}"#,
+            r#"{"map":[true],"width":0}"#,
+            r#"{"map":[true,false,true],"width":2}"#,
+        ] {
+            assert!(serde_json::from_str::<Problem>(invalid).is_err());
+        }
+    }
 }
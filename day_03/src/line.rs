@@ -0,0 +1,176 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Context;
+
+use crate::Problem;
+
+/// Line going `right` columns for every `down` rows. Unlike `Slope`, which
+/// jumps `down` rows at a time, the line visits every row and takes the
+/// column it crosses the middle of the row in, so `1/3` and `2/3` are
+/// possible. Lines with `down` 1 visit the same cells as the `Slope`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RationalSlope {
+    pub right: usize,
+    pub down: usize,
+}
+
+impl RationalSlope {
+    /// Cells the line visits, one per row, starting at the top left corner.
+    /// Columns aren't wrapped and the walk never ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `down` is 0
+    pub fn walk(self) -> impl Iterator<Item = (usize, usize)> {
+        assert!(self.down > 0, "slope has to go down");
+
+        LineWalk {
+            step: self.right / self.down,
+            remainder: self.right % self.down,
+            down: self.down,
+            x: 0,
+            y: 0,
+            // rounds columns half down
+            error: (self.down - 1) / 2,
+        }
+    }
+}
+
+impl FromStr for RationalSlope {
+    type Err = anyhow::Error;
+
+    /// Parses `right/down`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right, down) = s.split_once('/').context("expected right/down")?;
+        let slope = RationalSlope {
+            right: right.parse()?,
+            down: down.parse()?,
+        };
+
+        if slope.down == 0 {
+            anyhow::bail!("slope has to go down");
+        }
+
+        Ok(slope)
+    }
+}
+
+impl fmt::Display for RationalSlope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.right, self.down)
+    }
+}
+
+/// Bresenham-style walk, `error` keeps the fractional part of the column
+/// scaled by `down` so no division is needed per row
+struct LineWalk {
+    step: usize,
+    remainder: usize,
+    down: usize,
+    x: usize,
+    y: usize,
+    error: usize,
+}
+
+impl Iterator for LineWalk {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cell = (self.x, self.y);
+
+        self.y += 1;
+        self.x += self.step;
+        self.error += self.remainder;
+        if self.error >= self.down {
+            self.error -= self.down;
+            self.x += 1;
+        }
+
+        Some(cell)
+    }
+}
+
+/// # Panics
+///
+/// Panics if the slope doesn't go down
+#[must_use]
+pub fn count_collisions_rational(p: &Problem, slope: RationalSlope) -> usize {
+    count_collisions_for_rational_slopes(p, &[slope])[0]
+}
+
+/// Counts collisions of every line in one walk over the rows of the map
+///
+/// # Panics
+///
+/// Panics if a slope doesn't go down
+#[must_use]
+pub fn count_collisions_for_rational_slopes(p: &Problem, slopes: &[RationalSlope]) -> Vec<usize> {
+    let mut walks: Vec<_> = slopes.iter().map(|s| s.walk()).collect();
+    let mut hits = vec![0; slopes.len()];
+
+    for _ in 0..p.height() {
+        for (hits, walk) in hits.iter_mut().zip(&mut walks) {
+            if let Some((x, y)) = walk.next() {
+                if p.is_tree(x, y) {
+                    *hits += 1;
+                }
+            }
+        }
+    }
+
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count_collisions;
+
+    const TEST_INPUT: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn test_walk() {
+        let columns = |s: &str| -> Vec<_> {
+            let slope: RationalSlope = s.parse().unwrap();
+            slope.walk().take(7).map(|(x, _)| x).collect()
+        };
+
+        assert_eq!(columns("3/1"), vec![0, 3, 6, 9, 12, 15, 18]);
+        assert_eq!(columns("1/3"), vec![0, 0, 1, 1, 1, 2, 2]);
+        assert_eq!(columns("2/3"), vec![0, 1, 1, 2, 3, 3, 4]);
+        assert_eq!(columns("7/2"), vec![0, 3, 7, 10, 14, 17, 21]);
+        assert_eq!(columns("0/5"), vec![0; 7]);
+        assert!("1/0".parse::<RationalSlope>().is_err());
+        assert!("1,3".parse::<RationalSlope>().is_err());
+    }
+
+    #[test]
+    fn test_count_collisions_rational() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        for right in [1, 3, 5, 7] {
+            assert_eq!(
+                count_collisions_rational(&p, RationalSlope { right, down: 1 }),
+                count_collisions(&p, right, 1)
+            );
+        }
+
+        let slopes = ["1/3", "2/3", "6/2"].map(|s| s.parse().unwrap());
+
+        assert_eq!(
+            count_collisions_for_rational_slopes(&p, &slopes),
+            vec![5, 2, 7]
+        );
+    }
+}
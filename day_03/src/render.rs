@@ -0,0 +1,266 @@
+use std::io::{self, Write};
+
+use crate::{Problem, RationalSlope, Slope};
+
+pub type Rgb = [u8; 3];
+
+const OPEN_COLOR: Rgb = [255, 255, 255];
+const TREE_COLOR: Rgb = [34, 100, 34];
+
+/// Markers and colors given to overlaid paths in turn by `Overlay::nth`
+const STYLES: [(char, char, Rgb); 4] = [
+    ('O', 'X', [220, 40, 40]),
+    ('o', 'x', [40, 80, 220]),
+    ('@', '%', [230, 150, 20]),
+    ('+', '*', [150, 40, 180]),
+];
+
+/// Cells visited by a slope or a line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Path {
+    Slope(Slope),
+    Line(RationalSlope),
+}
+
+impl Path {
+    /// Visited cells in the first `height` rows, columns aren't wrapped
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path doesn't go down
+    fn cells(self, height: usize) -> Vec<(usize, usize)> {
+        match self {
+            Path::Slope(slope) => {
+                assert!(slope.down > 0, "slope has to go down");
+
+                (0..height)
+                    .step_by(slope.down)
+                    .map(|y| (y / slope.down * slope.right, y))
+                    .collect()
+            }
+            Path::Line(line) => line.walk().take(height).collect(),
+        }
+    }
+}
+
+impl From<Slope> for Path {
+    fn from(slope: Slope) -> Self {
+        Path::Slope(slope)
+    }
+}
+
+impl From<RationalSlope> for Path {
+    fn from(line: RationalSlope) -> Self {
+        Path::Line(line)
+    }
+}
+
+/// How a path is drawn over the map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overlay {
+    pub path: Path,
+    /// Marker of open cells on the path
+    pub open: char,
+    /// Marker of trees on the path
+    pub tree: char,
+    /// Color of the path in images, trees on it are drawn darker
+    pub color: Rgb,
+}
+
+impl Overlay {
+    /// `O` for open cells and `X` for trees
+    #[must_use]
+    pub fn new(path: impl Into<Path>) -> Self {
+        Overlay::nth(path, 0)
+    }
+
+    /// Style of the `n`th of several overlaid paths, styles repeat after
+    /// the fourth
+    #[must_use]
+    pub fn nth(path: impl Into<Path>, n: usize) -> Self {
+        let (open, tree, color) = STYLES[n % STYLES.len()];
+
+        Overlay {
+            path: path.into(),
+            open,
+            tree,
+            color,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cell {
+    Open,
+    Tree,
+    /// On the path of the overlay with this index
+    Path(usize, bool),
+}
+
+/// Map tiled to the right until it holds every path, overlays listed first
+/// are drawn over later ones
+struct Canvas {
+    width: usize,
+    cells: Vec<Cell>,
+}
+
+impl Canvas {
+    /// # Panics
+    ///
+    /// Panics if a path doesn't go down
+    fn new(p: &Problem, overlays: &[Overlay]) -> Self {
+        let paths: Vec<_> = overlays.iter().map(|o| o.path.cells(p.height())).collect();
+
+        let needed = paths
+            .iter()
+            .filter_map(|cells| cells.last())
+            .map(|(x, _)| x + 1)
+            .max()
+            .unwrap_or_default();
+        let width = needed.div_ceil(p.width()).max(1) * p.width();
+
+        let mut cells: Vec<_> = (0..p.height())
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if p.is_tree(x, y) {
+                    Cell::Tree
+                } else {
+                    Cell::Open
+                }
+            })
+            .collect();
+
+        for (i, path) in paths.iter().enumerate().rev() {
+            for &(x, y) in path {
+                cells[y * width + x] = Cell::Path(i, p.is_tree(x, y));
+            }
+        }
+
+        Canvas { width, cells }
+    }
+}
+
+/// Map with the paths of `overlays` marked on it, one line per row
+///
+/// # Panics
+///
+/// Panics if a path doesn't go down
+#[must_use]
+pub fn render(p: &Problem, overlays: &[Overlay]) -> String {
+    let canvas = Canvas::new(p, overlays);
+    let mut out = String::with_capacity(canvas.cells.len() + p.height());
+
+    for row in canvas.cells.chunks(canvas.width) {
+        out.extend(row.iter().map(|&cell| match cell {
+            Cell::Open => '.',
+            Cell::Tree => '#',
+            Cell::Path(i, false) => overlays[i].open,
+            Cell::Path(i, true) => overlays[i].tree,
+        }));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Writes `render` as a binary PPM image with a pixel per cell, for maps
+/// too long to read as text
+///
+/// # Errors
+///
+/// Returns error if writing fails
+///
+/// # Panics
+///
+/// Panics if a path doesn't go down
+pub fn write_ppm<W: Write>(p: &Problem, overlays: &[Overlay], mut out: W) -> io::Result<()> {
+    let canvas = Canvas::new(p, overlays);
+
+    write!(out, "P6\n{} {}\n255\n", canvas.width, p.height())?;
+
+    let pixels: Vec<u8> = canvas
+        .cells
+        .iter()
+        .flat_map(|&cell| match cell {
+            Cell::Open => OPEN_COLOR,
+            Cell::Tree => TREE_COLOR,
+            Cell::Path(i, false) => overlays[i].color,
+            Cell::Path(i, true) => overlays[i].color.map(|c| c / 2),
+        })
+        .collect();
+
+    out.write_all(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn test_render() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let rendered = render(&p, &[Overlay::new(Slope { right: 3, down: 1 })]);
+        let rows: Vec<_> = rendered.lines().collect();
+
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], "O.##.........##.........##.......");
+        assert_eq!(rows[1], "#..O#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[2], ".#....X..#..#....#..#..#....#..#.");
+        assert_eq!(rendered.matches('X').count(), 7);
+        assert_eq!(rendered.matches('O').count(), 4);
+
+        let rendered = render(
+            &p,
+            &[
+                Overlay::nth(Slope { right: 1, down: 1 }, 0),
+                Overlay::nth(Slope { right: 1, down: 2 }, 1),
+            ],
+        );
+
+        assert_eq!(rendered.lines().next().unwrap().len(), 11);
+        assert_eq!(rendered.matches(['O', 'X']).count(), 11);
+        assert_eq!(rendered.matches(['o', 'x']).count(), 5);
+        assert_eq!(render(&p, &[]), p.to_string());
+
+        // `1/1` visits the same cells as `1,1`
+        let line = RationalSlope { right: 1, down: 1 };
+        assert_eq!(
+            render(&p, &[Overlay::new(line)]),
+            render(&p, &[Overlay::new(Slope { right: 1, down: 1 })])
+        );
+
+        let rendered = render(&p, &[Overlay::new(RationalSlope { right: 1, down: 3 })]);
+        let rows: Vec<_> = rendered.lines().collect();
+
+        assert_eq!(rows[0].len(), 11);
+        assert_eq!(rendered.matches(['O', 'X']).count(), 11);
+        // one mark per row, columns of `1/3` are rounded to the nearest
+        assert_eq!(&rows[1][..2], "X.");
+        assert_eq!(&rows[2][..2], ".X");
+        assert_eq!(&rows[3][..2], ".O");
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let mut image = Vec::new();
+        write_ppm(&p, &[Overlay::new(Slope { right: 3, down: 1 })], &mut image).unwrap();
+
+        let header = b"P6\n33 11\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 33 * 11 * 3);
+    }
+}
@@ -1,17 +1,65 @@
-use std::{env, fs};
+use std::{env, fs, path::PathBuf};
 
-use anyhow::Context;
-use day_04::{count_passports_with_required_fields, Problem, count_valid_passports};
+use common::Config;
+use day_04::{parse_with_mode, report, ParseMode, Parsed, Policy};
+
+const USAGE: &str = "Usage: day_04 [--report] [--policy path] [--mode strict|lenient|warn] [input]";
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let mut args = args.into_iter();
+    let mut policy_path = config.param::<PathBuf>(4, "policy")?;
+    let mut mode = config.param_or(4, "mode", ParseMode::default())?;
+    let mut input = None;
+    let mut print_report = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--policy" => {
+                policy_path = Some(args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?.into())
+            }
+            "--mode" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+                mode = value.parse().map_err(|_| anyhow::anyhow!(USAGE))?;
+            }
+            "--report" => print_report = true,
+            _ if arg.starts_with("--") || input.is_some() => anyhow::bail!(USAGE),
+            _ => input = Some(arg),
+        }
+    }
+
+    let policy = match policy_path {
+        #[cfg(feature = "policy-files")]
+        Some(path) => Policy::load(&path)?,
+        #[cfg(not(feature = "policy-files"))]
+        Some(_) => anyhow::bail!("day_04 is built without the policy-files feature"),
+        None => Policy::standard().clone(),
+    };
+
+    let input_path = config.input_path(4, input.as_deref());
     let content = fs::read_to_string(input_path)?;
-    let p: Problem = content.parse()?;
+    let Parsed {
+        problem: p,
+        spans,
+        warnings,
+    } = parse_with_mode(&content, mode)?;
+
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    if print_report {
+        for passport in report(&p, &spans, &policy) {
+            println!("{passport}");
+        }
+
+        return Ok(());
+    }
 
-    let passports_with_required_fields_count = count_passports_with_required_fields(&p);
+    let passports_with_required_fields_count = policy.presence_only().count_valid(&p);
     println!("Part 1: {passports_with_required_fields_count}");
 
-    let valid_passports_count = count_valid_passports(&p);
+    let valid_passports_count = policy.count_valid(&p);
     println!("Part 2: {valid_passports_count}");
 
     Ok(())
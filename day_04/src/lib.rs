@@ -1,8 +1,33 @@
-use std::str::FromStr;
-
-use anyhow::{anyhow, bail};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::{anyhow, bail, Context};
+use parse_display::{Display, FromStr};
+
+mod pattern;
+mod rules;
+mod valid;
+
+pub use pattern::Pattern;
+pub use rules::{Constraint, FieldError, FieldRule, Policy, UnitRange, STANDARD_POLICY};
+pub use valid::{EyeColor, HairColor, Height, InvalidPassport, PassportId, ValidPassport};
+
+/// How `Passport::parse_with_mode` treats unknown and duplicate keys
+#[derive(Clone, Copy, Debug, Default, Display, FromStr, PartialEq, Eq)]
+#[display(style = "lowercase")]
+pub enum ParseMode {
+    /// Unknown and duplicate keys are errors
+    #[default]
+    Strict,
+    /// Unknown keys are kept as extras and later duplicates replace earlier
+    /// values, like the parser did before there were modes
+    Lenient,
+    /// Like `Lenient`, but every unknown or duplicate key is reported as a
+    /// warning
+    Warn,
+}
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Passport {
     byr: Option<String>,
     iyr: Option<String>,
@@ -12,231 +37,335 @@ pub struct Passport {
     ecl: Option<String>,
     pid: Option<String>,
     cid: Option<String>,
+    /// Fields with unknown keys, only kept by lenient parsing
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    extras: BTreeMap<String, String>,
 }
 
-impl FromStr for Passport {
-    type Err = anyhow::Error;
+impl Passport {
+    fn field_mut(&mut self, key: &str) -> Option<&mut Option<String>> {
+        match key {
+            "byr" => Some(&mut self.byr),
+            "iyr" => Some(&mut self.iyr),
+            "eyr" => Some(&mut self.eyr),
+            "hgt" => Some(&mut self.hgt),
+            "hcl" => Some(&mut self.hcl),
+            "ecl" => Some(&mut self.ecl),
+            "pid" => Some(&mut self.pid),
+            "cid" => Some(&mut self.cid),
+            _ => None,
+        }
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let kv_pairs = s
-            .lines()
-            .flat_map(|l| l.split_ascii_whitespace())
-            .collect::<Vec<_>>();
+    /// Value of the field with key `key`, unknown keys are looked up in the
+    /// extras. `None` if the field is missing.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let value = match key {
+            "byr" => &self.byr,
+            "iyr" => &self.iyr,
+            "eyr" => &self.eyr,
+            "hgt" => &self.hgt,
+            "hcl" => &self.hcl,
+            "ecl" => &self.ecl,
+            "pid" => &self.pid,
+            "cid" => &self.cid,
+            _ => return self.extras.get(key).map(String::as_str),
+        };
+
+        value.as_deref()
+    }
 
+    /// Fields with unknown keys kept by lenient parsing
+    #[must_use]
+    pub fn extras(&self) -> &BTreeMap<String, String> {
+        &self.extras
+    }
+
+    /// Parses `key:value` pairs separated by whitespace, returns warnings
+    /// about unknown and duplicate keys in `ParseMode::Warn`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a pair is malformed, or on unknown and duplicate keys
+    /// in `ParseMode::Strict`
+    pub fn parse_with_mode(
+        s: &str,
+        mode: ParseMode,
+    ) -> Result<(Passport, Vec<String>), anyhow::Error> {
         let mut p = Passport::default();
+        let mut warnings = Vec::new();
 
-        for kv in kv_pairs {
-            match kv
+        for kv in s.split_ascii_whitespace() {
+            let (k, v) = kv
                 .split_once(':')
-                .ok_or_else(|| anyhow!("malformed kv pair {}", kv))?
-            {
-                ("byr", v) => {
-                    p.byr = Some(v.to_string());
-                }
-                ("iyr", v) => {
-                    p.iyr = Some(v.to_string());
-                }
-                ("eyr", v) => {
-                    p.eyr = Some(v.to_string());
-                }
-                ("hgt", v) => {
-                    p.hgt = Some(v.to_string());
-                }
-                ("hcl", v) => {
-                    p.hcl = Some(v.to_string());
-                }
-                ("ecl", v) => {
-                    p.ecl = Some(v.to_string());
-                }
-                ("pid", v) => {
-                    p.pid = Some(v.to_string());
-                }
-                ("cid", v) => {
-                    p.cid = Some(v.to_string());
-                }
-                (k, _) => bail!("unknown key {}", k),
+                .ok_or_else(|| anyhow!("malformed kv pair {}", kv))?;
+
+            let (known, previous) = match p.field_mut(k) {
+                Some(field) => (true, field.replace(v.to_string())),
+                None => (false, p.extras.insert(k.to_string(), v.to_string())),
+            };
+
+            let problem = match (known, previous) {
+                (_, Some(_)) => "duplicate key",
+                (false, None) => "unknown key",
+                (true, None) => continue,
+            };
+
+            match mode {
+                ParseMode::Strict => bail!("{problem} {k}"),
+                ParseMode::Lenient => {}
+                ParseMode::Warn => warnings.push(format!("{problem} {k}")),
             }
         }
 
-        Ok(p)
+        Ok((p, warnings))
     }
 }
 
-#[derive(Debug)]
-pub struct Problem {
-    passports: Vec<Passport>,
-}
-
-impl FromStr for Problem {
+impl FromStr for Passport {
     type Err = anyhow::Error;
 
+    /// Parses in `ParseMode::Strict`
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let passports = s
-            .split("\n\n")
-            .map(str::parse)
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(Problem { passports })
+        Passport::parse_with_mode(s, ParseMode::Strict).map(|(p, _)| p)
     }
 }
 
-#[must_use]
-pub fn passport_has_required_fields(p: &Passport) -> bool {
-    let Passport {
-        byr,
-        iyr,
-        eyr,
-        hgt,
-        hcl,
-        ecl,
-        pid,
-        cid: _,
-    } = p;
-
-    byr.is_some()
-        && iyr.is_some()
-        && eyr.is_some()
-        && hgt.is_some()
-        && hcl.is_some()
-        && ecl.is_some()
-        && pid.is_some()
+/// Known fields come first, then the extras, which `FromStr` rejects as
+/// unknown keys. Read passports with extras back with
+/// `Passport::parse_with_mode` in `ParseMode::Lenient`.
+impl fmt::Display for Passport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Passport {
+            byr,
+            iyr,
+            eyr,
+            hgt,
+            hcl,
+            ecl,
+            pid,
+            cid,
+            extras,
+        } = self;
+
+        let kv_pairs = [
+            ("byr", byr),
+            ("iyr", iyr),
+            ("eyr", eyr),
+            ("hgt", hgt),
+            ("hcl", hcl),
+            ("ecl", ecl),
+            ("pid", pid),
+            ("cid", cid),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| v.as_ref().map(|v| format!("{k}:{v}")))
+        .chain(extras.iter().map(|(k, v)| format!("{k}:{v}")))
+        .collect::<Vec<_>>();
+
+        write!(f, "{}", kv_pairs.join(" "))
+    }
 }
 
-#[must_use]
-pub fn count_passports_with_required_fields(p: &Problem) -> usize {
-    let Problem { passports } = p;
-
-    passports
-        .iter()
-        .filter(|p| passport_has_required_fields(p))
-        .count()
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Problem {
+    passports: Vec<Passport>,
 }
 
-#[must_use]
-pub fn is_passport_valid(p: &Passport) -> bool {
-    let Passport {
-        byr,
-        iyr,
-        eyr,
-        hgt,
-        hcl,
-        ecl,
-        pid,
-        cid: _,
-    } = p;
-
-    let Some(birth_year) = byr else {
-        return false;
-    };
-
-    let birth_year = birth_year.parse::<u64>().unwrap_or(0);
+/// 1-based lines of the input a passport spans
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineSpan {
+    pub first: usize,
+    pub last: usize,
+}
 
-    if (1920..=2002).contains(&birth_year) {
-    } else {
-        return false;
+impl fmt::Display for LineSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "line {}", self.first)
+        } else {
+            write!(f, "lines {}-{}", self.first, self.last)
+        }
     }
+}
 
-    let Some(issue_year) = iyr else {
-        return false;
-    };
-
-    let issue_year = issue_year.parse::<u64>().unwrap_or(0);
+/// Unknown or duplicate key found by `ParseMode::Warn`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// 1-based index of the passport in the input
+    pub index: usize,
+    pub lines: LineSpan,
+    pub message: String,
+}
 
-    if (2010..=2020).contains(&issue_year) {
-    } else {
-        return false;
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "passport {} ({}): {}",
+            self.index, self.lines, self.message
+        )
     }
+}
 
-    let Some(expiry_year) = eyr else {
-        return false;
-    };
+#[derive(Debug, PartialEq)]
+pub struct Parsed {
+    pub problem: Problem,
+    /// Lines every passport spans
+    pub spans: Vec<LineSpan>,
+    pub warnings: Vec<ParseWarning>,
+}
 
-    let expiry_year = expiry_year.parse::<u64>().unwrap_or(0);
+/// Parses the input with passports separated by blank lines, keeping track
+/// of the lines every passport spans
+///
+/// # Errors
+///
+/// Returns error naming the passport and its lines if one is malformed, see
+/// `Passport::parse_with_mode`
+pub fn parse_with_mode(s: &str, mode: ParseMode) -> Result<Parsed, anyhow::Error> {
+    let lines: Vec<_> = s.lines().collect();
+    let mut parsed = Parsed {
+        problem: Problem {
+            passports: Vec::new(),
+        },
+        spans: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let mut first = 1;
+
+    for group in lines.split(|l| l.trim().is_empty()) {
+        if !group.is_empty() {
+            let index = parsed.spans.len() + 1;
+            let span = LineSpan {
+                first,
+                last: first + group.len() - 1,
+            };
+            let (passport, warnings) = Passport::parse_with_mode(&group.join("\n"), mode)
+                .with_context(|| format!("passport {index} ({span})"))?;
+
+            parsed.problem.passports.push(passport);
+            parsed.spans.push(span);
+            parsed
+                .warnings
+                .extend(warnings.into_iter().map(|message| ParseWarning {
+                    index,
+                    lines: span,
+                    message,
+                }));
+        }
 
-    if (2020..=2030).contains(&expiry_year) {
-    } else {
-        return false;
+        first += group.len() + 1;
     }
 
-    let Some(hgt) = hgt else {
-        return false;
-    };
+    Ok(parsed)
+}
 
-    if hgt.ends_with("cm") {
-        let Some(hgt) = hgt.strip_suffix("cm") else {
-            return false;
-        };
+/// Parses the input like `Problem::from_str` and also returns the lines
+/// every passport spans
+///
+/// # Errors
+///
+/// Returns error naming the passport and its lines if one is malformed
+pub fn parse_with_spans(s: &str) -> Result<(Problem, Vec<LineSpan>), anyhow::Error> {
+    parse_with_mode(s, ParseMode::Strict).map(|parsed| (parsed.problem, parsed.spans))
+}
 
-        let hgt = hgt.parse::<u64>().unwrap_or(0);
+impl FromStr for Problem {
+    type Err = anyhow::Error;
 
-        if (150..=193).contains(&hgt) {
-        } else {
-            return false;
-        }
-    } else if hgt.ends_with("in") {
-        let Some(hgt) = hgt.strip_suffix("in") else {
-            return false;
-        };
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_with_spans(s).map(|(p, _)| p)
+    }
+}
 
-        let hgt = hgt.parse::<u64>().unwrap_or(0);
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, passport) in self.passports.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
 
-        if (59..=76).contains(&hgt) {
-        } else {
-            return false;
+            writeln!(f, "{passport}")?;
         }
-    } else {
-        return false;
+
+        Ok(())
     }
+}
 
-    let Some(hair_color) = hcl else {
-        return false;
-    };
+/// Problems found in a passport
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PassportReport {
+    /// 1-based index of the passport in the input
+    pub index: usize,
+    pub lines: LineSpan,
+    pub errors: Vec<FieldError>,
+}
 
-    if hair_color.starts_with('#') {
-        let Some(hair_color) = hair_color.strip_prefix('#') else {
-            return false;
-        };
+impl fmt::Display for PassportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "passport {} ({}): ", self.index, self.lines)?;
 
-        if hair_color.chars().all(|c| c.is_ascii_hexdigit()) {
-        } else {
-            return false;
+        if self.errors.is_empty() {
+            return write!(f, "valid");
         }
-    } else {
-        return false;
-    }
 
-    let Some(eye_color) = ecl else {
-        return false;
-    };
-
-    match eye_color.as_str() {
-        "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth" => {}
-        _ => {
-            return false;
-        }
+        let errors: Vec<_> = self.errors.iter().map(ToString::to_string).collect();
+        write!(f, "{}", errors.join("; "))
     }
+}
 
-    let Some(pid) = pid else {
-        return false;
-    };
+/// Validates every passport of `p`, `spans` are the ones
+/// `parse_with_spans` returned with it
+#[must_use]
+pub fn report(p: &Problem, spans: &[LineSpan], policy: &Policy) -> Vec<PassportReport> {
+    p.passports
+        .iter()
+        .zip(spans)
+        .enumerate()
+        .map(|(i, (passport, &lines))| PassportReport {
+            index: i + 1,
+            lines,
+            errors: policy.validate(passport),
+        })
+        .collect()
+}
 
-    if pid.len() == 9 && pid.chars().all(|c| c.is_ascii_digit()) {
-    } else {
-        return false;
-    }
+#[must_use]
+pub fn passport_has_required_fields(p: &Passport) -> bool {
+    Policy::standard().presence_only().is_valid(p)
+}
 
-    true
+#[must_use]
+pub fn count_passports_with_required_fields(p: &Problem) -> usize {
+    Policy::standard().presence_only().count_valid(p)
 }
 
+/// Whether `p` satisfies the standard policy. Numbers are read like
+/// `u64::from_str` and hair colors take any number of hex digits of either
+/// case, so `02002`, `+170cm`, `#ABCDEF` or `#12` are accepted.
 #[must_use]
-pub fn count_valid_passports(p: &Problem) -> usize {
-    let Problem { passports } = p;
+pub fn is_passport_valid(p: &Passport) -> bool {
+    Policy::standard().is_valid(p)
+}
 
-    passports
+/// Passports of `p` satisfying the standard policy, parsed
+#[must_use]
+pub fn valid_passports(p: &Problem) -> Vec<ValidPassport> {
+    p.passports
         .iter()
-        .map(is_passport_valid)
-        .filter(|valid| *valid)
-        .count()
+        .filter_map(|p| ValidPassport::try_from(p).ok())
+        .collect()
+}
+
+#[must_use]
+pub fn count_valid_passports(p: &Problem) -> usize {
+    Policy::standard().count_valid(p)
 }
 
 #[cfg(test)]
@@ -303,6 +432,7 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
     }
 
     #[test]
+    #[allow(unknown_lints, clippy::map_all_any_identity)]
     fn test_valid_passports() {
         let Problem { passports } = TEST_VALID_PASSPORTS.parse().unwrap();
 
@@ -310,6 +440,26 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
         assert!(passports.iter().map(is_passport_valid).all(|valid| valid));
     }
 
+    #[test]
+    fn test_lenient_formats() {
+        let valid = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f";
+        let passport = |from: &str, to: &str| valid.replace(from, to).parse::<Passport>().unwrap();
+
+        assert!(is_passport_valid(&valid.parse().unwrap()));
+
+        for (from, to) in [
+            ("hcl:#623a2f", "hcl:#ABCDEF"),
+            ("hcl:#623a2f", "hcl:#12"),
+            ("hgt:74in", "hgt:+170cm"),
+            ("byr:1980", "byr:02002"),
+        ] {
+            let p = passport(from, to);
+
+            assert!(is_passport_valid(&p), "{to}");
+            assert!(ValidPassport::try_from(&p).is_ok(), "{to}");
+        }
+    }
+
     #[test]
     fn test_count_valid_passports() {
         let p1: Problem = TEST_INVALID_PASSPORTS.parse().unwrap();
@@ -317,5 +467,102 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
 
         assert_eq!(count_valid_passports(&p1), 0);
         assert_eq!(count_valid_passports(&p2), 4);
+        assert_eq!(valid_passports(&p2).len(), 4);
+    }
+
+    #[test]
+    fn test_report() {
+        let (p, spans) = parse_with_spans(TEST_INPUT).unwrap();
+        let reports: Vec<_> = report(&p, &spans, Policy::standard())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            reports,
+            [
+                "passport 1 (lines 1-2): valid",
+                "passport 2 (lines 4-5): hgt missing",
+                "passport 3 (lines 7-10): valid",
+                "passport 4 (lines 12-13): byr missing",
+            ]
+        );
+
+        let error = parse_with_spans("byr:1937\n\n\nhgt:1\nfoo")
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(error, "passport 2 (lines 4-5)");
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let input = "byr:1937 foo:bar\niyr:2017\n\neyr:2020 eyr:2030 hgt:183cm";
+
+        assert_eq!(
+            parse_with_mode(input, ParseMode::Strict)
+                .unwrap_err()
+                .to_string(),
+            "passport 1 (lines 1-2)"
+        );
+        assert!(parse_with_mode("eyr:2020 eyr:2030", ParseMode::Strict).is_err());
+
+        let lenient = parse_with_mode(input, ParseMode::Lenient).unwrap();
+        let warn = parse_with_mode(input, ParseMode::Warn).unwrap();
+
+        assert!(lenient.warnings.is_empty());
+        assert_eq!(lenient.problem, warn.problem);
+
+        let warnings: Vec<_> = warn.warnings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            warnings,
+            [
+                "passport 1 (lines 1-2): unknown key foo",
+                "passport 2 (line 4): duplicate key eyr",
+            ]
+        );
+
+        let passports = &warn.problem.passports;
+        assert_eq!(passports[0].get("foo"), Some("bar"));
+        assert_eq!(passports[1].get("eyr"), Some("2030"));
+        assert_eq!(passports[0].to_string(), "byr:1937 iyr:2017 foo:bar");
+
+        let (p, warnings) = Passport::parse_with_mode("foo:a foo:b", ParseMode::Warn).unwrap();
+        assert_eq!(p.get("foo"), Some("b"));
+        assert_eq!(warnings, ["unknown key foo", "duplicate key foo"]);
+        assert!(Passport::parse_with_mode("foo:a", ParseMode::Strict).is_err());
+
+        assert_eq!("mode".parse::<ParseMode>().ok(), None);
+        assert_eq!("warn".parse::<ParseMode>().unwrap(), ParseMode::Warn);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let p: Problem = TEST_VALID_PASSPORTS.parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+        let p: Problem = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(count_valid_passports(&p), 4);
+        assert_eq!(serde_json::to_string(&p).unwrap(), json);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert!(p.to_string().starts_with(
+            "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:147\n\n"
+        ));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+
+        // extras only read back leniently
+        let input = "byr:1937 foo:bar\niyr:2017\n\neyr:2030 hgt:183cm";
+        let p = parse_with_mode(input, ParseMode::Lenient).unwrap().problem;
+        let s = p.to_string();
+
+        assert!(s.parse::<Problem>().is_err());
+        assert_eq!(parse_with_mode(&s, ParseMode::Lenient).unwrap().problem, p);
+        assert_eq!(parse_with_mode(&s, ParseMode::Warn).unwrap().problem, p);
     }
 }
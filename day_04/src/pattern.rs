@@ -0,0 +1,217 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+use anyhow::{bail, Context};
+
+/// Small regex-like pattern that has to match a whole value. Supports
+/// literal characters, `.`, classes like `[0-9a-f]` or `[^#]`, `\` escapes
+/// and the quantifiers `?`, `*`, `+`, `{n}`, `{n,}` and `{n,m}`. Groups and
+/// alternation are not supported, so `(`, `)` and `|` have to be escaped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+    elements: Vec<Element>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Atom {
+    Any,
+    Char(char),
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Any => true,
+            Atom::Char(expected) => c == *expected,
+            Atom::Class { negated, ranges } => {
+                ranges.iter().any(|&(low, high)| (low..=high).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+/// Atom repeated between `min` and `max` times
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Element {
+    atom: Atom,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Pattern {
+    #[must_use]
+    pub fn is_match(&self, value: &str) -> bool {
+        let chars: Vec<_> = value.chars().collect();
+
+        match_elements(&self.elements, &chars)
+    }
+}
+
+/// Match of `elements` against all of `chars` that follows every way of
+/// splitting the characters between the elements at once, so it takes
+/// polynomial time even for patterns like `a*a*a*b`. A state is an element
+/// and how many times it's repeated so far, repetitions of an unbounded
+/// element past its `min` all lead to the same state.
+fn match_elements(elements: &[Element], chars: &[char]) -> bool {
+    let mut states = HashSet::new();
+    add_state(elements, &mut states, 0, 0);
+
+    for &c in chars {
+        let mut next = HashSet::new();
+
+        for &(i, n) in &states {
+            let Some(element) = elements.get(i) else {
+                continue;
+            };
+
+            if element.max.is_some_and(|max| n >= max) || !element.atom.matches(c) {
+                continue;
+            }
+
+            let n = match element.max {
+                Some(_) => n + 1,
+                None => (n + 1).min(element.min),
+            };
+
+            add_state(elements, &mut next, i, n);
+        }
+
+        if next.is_empty() {
+            return false;
+        }
+
+        states = next;
+    }
+
+    states.contains(&(elements.len(), 0))
+}
+
+/// Adds the state and the ones after it that are reached without taking a
+/// character, by leaving elements repeated at least `min` times
+fn add_state(elements: &[Element], states: &mut HashSet<(usize, usize)>, i: usize, n: usize) {
+    if !states.insert((i, n)) {
+        return;
+    }
+
+    if elements.get(i).is_some_and(|element| n >= element.min) {
+        add_state(elements, states, i + 1, 0);
+    }
+}
+
+/// Next character of a class, `None` at its `]`
+fn class_char(chars: &mut Peekable<Chars<'_>>) -> Result<Option<char>, anyhow::Error> {
+    match chars.next() {
+        Some('\\') => chars.next().context("dangling '\\'").map(Some),
+        Some(']') => Ok(None),
+        Some(c) => Ok(Some(c)),
+        None => bail!("unclosed '['"),
+    }
+}
+
+/// Parses a class after its `[`
+fn parse_class(chars: &mut Peekable<Chars<'_>>) -> Result<Atom, anyhow::Error> {
+    let negated = chars.next_if_eq(&'^').is_some();
+    let mut ranges = Vec::new();
+
+    while let Some(low) = class_char(chars)? {
+        if chars.next_if_eq(&'-').is_none() {
+            ranges.push((low, low));
+            continue;
+        }
+
+        // '-' right before ']' is literal
+        let Some(high) = class_char(chars)? else {
+            ranges.extend([(low, low), ('-', '-')]);
+            break;
+        };
+
+        if low > high {
+            bail!("invalid range {low}-{high}");
+        }
+
+        ranges.push((low, high));
+    }
+
+    Ok(Atom::Class { negated, ranges })
+}
+
+/// Parses a repetition after its `{`
+fn parse_repetition(
+    chars: &mut Peekable<Chars<'_>>,
+) -> Result<(usize, Option<usize>), anyhow::Error> {
+    let mut body = String::new();
+
+    loop {
+        match chars.next().context("unclosed '{'")? {
+            '}' => break,
+            c => body.push(c),
+        }
+    }
+
+    let (min, max) = match body.split_once(',') {
+        None => {
+            let n = body.parse()?;
+            (n, Some(n))
+        }
+        Some((min, "")) => (min.parse()?, None),
+        Some((min, max)) => (min.parse()?, Some(max.parse()?)),
+    };
+
+    if max.is_some_and(|max| max < min) {
+        bail!("invalid repetition {{{body}}}");
+    }
+
+    Ok((min, max))
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars().peekable();
+        let mut elements = Vec::new();
+
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '.' => Atom::Any,
+                '[' => parse_class(&mut chars)?,
+                '\\' => Atom::Char(chars.next().context("dangling '\\'")?),
+                '?' | '*' | '+' | '{' => bail!("{c:?} doesn't follow anything in {s:?}"),
+                ']' | '}' => bail!("unmatched {c:?} in {s:?}"),
+                // groups and alternation aren't supported, escape them to match literally
+                '(' | ')' | '|' => bail!("unsupported {c:?} in {s:?}"),
+                c => Atom::Char(c),
+            };
+
+            let (min, max) = match chars.next_if(|c| matches!(c, '?' | '*' | '+' | '{')) {
+                Some('?') => (0, Some(1)),
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some(_) => parse_repetition(&mut chars)?,
+                None => (1, Some(1)),
+            };
+
+            elements.push(Element { atom, min, max });
+        }
+
+        Ok(Pattern {
+            source: s.to_string(),
+            elements,
+        })
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
@@ -0,0 +1,563 @@
+use std::{fmt, sync::OnceLock};
+#[cfg(feature = "policy-files")]
+use std::{fs, path::Path};
+
+#[cfg(feature = "policy-files")]
+use anyhow::{bail, Context};
+
+use crate::{Passport, Pattern, Problem};
+
+/// Policy file of part 2, the same rules as `Policy::standard`
+pub const STANDARD_POLICY: &str = include_str!("../policies/standard.toml");
+
+/// Range a number with the given unit has to be in, e.g. 150-193 for `cm`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "policy-files",
+    derive(serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct UnitRange {
+    #[cfg_attr(feature = "policy-files", serde(default))]
+    /// Suffix of the value, empty for plain numbers
+    pub unit: String,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl UnitRange {
+    #[must_use]
+    pub fn new(unit: &str, min: u64, max: u64) -> Self {
+        UnitRange {
+            unit: unit.to_string(),
+            min,
+            max,
+        }
+    }
+}
+
+/// What the value of a present field has to look like
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Any value
+    Any,
+    /// Year between `min` and `max`, read like any other number
+    Year { min: u32, max: u32 },
+    /// Number followed by one of the units and within its range
+    Number(Vec<UnitRange>),
+    /// Whole value matches the pattern
+    Pattern(Pattern),
+    /// One of the values
+    OneOf(Vec<String>),
+    /// Exactly this many digits
+    Digits(usize),
+}
+
+/// Value of a non-empty run of ASCII digits with an optional leading `+`,
+/// as `u64::from_str` reads it, in one pass without checking the digits
+/// first, `None` if it overflows
+pub(crate) fn parse_number(s: &str) -> Option<u64> {
+    let s = s.strip_prefix('+').unwrap_or(s);
+
+    if s.is_empty() {
+        return None;
+    }
+
+    s.bytes().try_fold(0_u64, |n, b| {
+        let digit = char::from(b).to_digit(10)?;
+
+        n.checked_mul(10)?.checked_add(u64::from(digit))
+    })
+}
+
+impl Constraint {
+    /// Explains why `value` breaks the constraint
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if it does
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Constraint::Any => Ok(()),
+            Constraint::Year { min, max } => match parse_number(value) {
+                Some(year) if (u64::from(*min)..=u64::from(*max)).contains(&year) => Ok(()),
+                Some(_) => Err(format!("not between {min} and {max}")),
+                None => Err("not a year".to_string()),
+            },
+            Constraint::Number(ranges) => {
+                let sign = usize::from(value.starts_with('+'));
+                let digits = value[sign..].bytes().take_while(u8::is_ascii_digit).count();
+                let (number, unit) = value.split_at(sign + digits);
+                // only formatted for errors
+                let expected = || {
+                    ranges
+                        .iter()
+                        .map(|r| format!("{}-{}{}", r.min, r.max, r.unit))
+                        .collect::<Vec<_>>()
+                        .join(" or ")
+                };
+
+                let Some(number) = parse_number(number) else {
+                    return Err(format!("not a number, expected {}", expected()));
+                };
+
+                let mut units = ranges.iter().filter(|r| r.unit == unit).peekable();
+
+                if units.peek().is_none() {
+                    Err(format!("unknown unit {unit:?}, expected {}", expected()))
+                } else if units.any(|r| (r.min..=r.max).contains(&number)) {
+                    Ok(())
+                } else {
+                    Err(format!("out of range, expected {}", expected()))
+                }
+            }
+            Constraint::Pattern(pattern) if pattern.is_match(value) => Ok(()),
+            Constraint::Pattern(pattern) => Err(format!("doesn't match {pattern}")),
+            Constraint::OneOf(values) if values.iter().any(|v| v == value) => Ok(()),
+            Constraint::OneOf(values) => Err(format!("not one of {}", values.join(", "))),
+            Constraint::Digits(length)
+                if value.len() == *length && value.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Ok(())
+            }
+            Constraint::Digits(length) => Err(format!("not a {length} digit number")),
+        }
+    }
+
+    #[must_use]
+    pub fn is_satisfied_by(&self, value: &str) -> bool {
+        self.check(value).is_ok()
+    }
+}
+
+/// Field of a passport breaking a rule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    /// `None` if the field is missing
+    pub value: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{} {:?} {}", self.field, value, self.reason),
+            None => write!(f, "{} {}", self.field, self.reason),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "policy-files",
+    derive(serde::Deserialize),
+    serde(try_from = "RuleEntry")
+)]
+pub struct FieldRule {
+    pub field: String,
+    /// Passports without the field break the rule, the constraint only
+    /// applies to present fields otherwise
+    pub required: bool,
+    pub constraint: Constraint,
+}
+
+impl FieldRule {
+    #[must_use]
+    pub fn required(field: &str, constraint: Constraint) -> Self {
+        FieldRule {
+            field: field.to_string(),
+            required: true,
+            constraint,
+        }
+    }
+
+    #[must_use]
+    pub fn optional(field: &str, constraint: Constraint) -> Self {
+        FieldRule {
+            field: field.to_string(),
+            required: false,
+            constraint,
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if the field of `p` breaks the rule
+    pub fn check(&self, p: &Passport) -> Result<(), FieldError> {
+        let error = |value: Option<&str>, reason| FieldError {
+            field: self.field.clone(),
+            value: value.map(str::to_string),
+            reason,
+        };
+
+        match p.get(&self.field) {
+            Some(value) => self
+                .constraint
+                .check(value)
+                .map_err(|reason| error(Some(value), reason)),
+            None if self.required => Err(error(None, "missing".to_string())),
+            None => Ok(()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_satisfied_by(&self, p: &Passport) -> bool {
+        self.check(p).is_ok()
+    }
+}
+
+#[cfg(feature = "policy-files")]
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct YearRange {
+    min: u32,
+    max: u32,
+}
+
+/// `FieldRule` as written in policy files, with one optional key per
+/// constraint
+#[cfg(feature = "policy-files")]
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleEntry {
+    field: String,
+    #[serde(default = "required_by_default")]
+    required: bool,
+    year: Option<YearRange>,
+    number: Option<Vec<UnitRange>>,
+    pattern: Option<String>,
+    one_of: Option<Vec<String>>,
+    digits: Option<usize>,
+}
+
+#[cfg(feature = "policy-files")]
+fn required_by_default() -> bool {
+    true
+}
+
+#[cfg(feature = "policy-files")]
+impl TryFrom<RuleEntry> for FieldRule {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: RuleEntry) -> Result<Self, Self::Error> {
+        let pattern = entry
+            .pattern
+            .map(|p| p.parse().with_context(|| format!("invalid pattern {p:?}")))
+            .transpose()?;
+
+        let mut constraints = [
+            entry.year.map(|y| Constraint::Year {
+                min: y.min,
+                max: y.max,
+            }),
+            entry.number.map(Constraint::Number),
+            pattern.map(Constraint::Pattern),
+            entry.one_of.map(Constraint::OneOf),
+            entry.digits.map(Constraint::Digits),
+        ]
+        .into_iter()
+        .flatten();
+
+        let constraint = constraints.next().unwrap_or(Constraint::Any);
+
+        if constraints.next().is_some() {
+            bail!("field {} has more than one constraint", entry.field);
+        }
+
+        Ok(FieldRule {
+            field: entry.field,
+            required: entry.required,
+            constraint,
+        })
+    }
+}
+
+/// Rules a valid passport satisfies all of
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "policy-files",
+    derive(serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Policy {
+    pub rules: Vec<FieldRule>,
+}
+
+impl Policy {
+    /// Policy of part 2, built on first use
+    #[must_use]
+    pub fn standard() -> &'static Self {
+        static STANDARD: OnceLock<Policy> = OnceLock::new();
+
+        STANDARD.get_or_init(|| {
+            let year = |min, max| Constraint::Year { min, max };
+            let hair_color = "#[0-9a-fA-F]*".parse().expect("built-in pattern is valid");
+            let eye_colors = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+            Policy {
+                rules: vec![
+                    FieldRule::required("byr", year(1920, 2002)),
+                    FieldRule::required("iyr", year(2010, 2020)),
+                    FieldRule::required("eyr", year(2020, 2030)),
+                    FieldRule::required(
+                        "hgt",
+                        Constraint::Number(vec![
+                            UnitRange::new("cm", 150, 193),
+                            UnitRange::new("in", 59, 76),
+                        ]),
+                    ),
+                    FieldRule::required("hcl", Constraint::Pattern(hair_color)),
+                    FieldRule::required(
+                        "ecl",
+                        Constraint::OneOf(eye_colors.map(str::to_string).to_vec()),
+                    ),
+                    FieldRule::required("pid", Constraint::Digits(9)),
+                    FieldRule::optional("cid", Constraint::Any),
+                ],
+            }
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if `s` isn't a valid policy
+    #[cfg(feature = "policy-files")]
+    pub fn from_toml(s: &str) -> Result<Self, anyhow::Error> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// # Errors
+    ///
+    /// Returns error if `s` isn't a valid policy
+    #[cfg(feature = "policy-files")]
+    pub fn from_json(s: &str) -> Result<Self, anyhow::Error> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Loads a policy file, JSON if its extension is `.json` and TOML
+    /// otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file can't be read or isn't a valid policy
+    #[cfg(feature = "policy-files")]
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read policy {}", path.display()))?;
+
+        let policy = if path.extension().is_some_and(|e| e == "json") {
+            Policy::from_json(&content)
+        } else {
+            Policy::from_toml(&content)
+        };
+
+        policy.with_context(|| format!("invalid policy {}", path.display()))
+    }
+
+    /// Same fields required, but any value goes (part 1)
+    #[must_use]
+    pub fn presence_only(&self) -> Self {
+        Policy {
+            rules: self
+                .rules
+                .iter()
+                .filter(|r| r.required)
+                .map(|r| FieldRule::required(&r.field, Constraint::Any))
+                .collect(),
+        }
+    }
+
+    /// Every rule `p` breaks, empty if it's valid
+    #[must_use]
+    pub fn validate(&self, p: &Passport) -> Vec<FieldError> {
+        self.rules.iter().filter_map(|r| r.check(p).err()).collect()
+    }
+
+    #[must_use]
+    pub fn is_valid(&self, p: &Passport) -> bool {
+        self.rules.iter().all(|r| r.is_satisfied_by(p))
+    }
+
+    #[must_use]
+    pub fn count_valid(&self, p: &Problem) -> usize {
+        p.passports.iter().filter(|p| self.is_valid(p)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints() {
+        let hgt = &Policy::standard().rules[3].constraint;
+
+        assert!(hgt.is_satisfied_by("60in"));
+        assert!(hgt.is_satisfied_by("190cm"));
+        assert!(!hgt.is_satisfied_by("190in"));
+        assert!(!hgt.is_satisfied_by("190"));
+        assert!(!hgt.is_satisfied_by("cm"));
+        assert!(hgt.is_satisfied_by("+170cm"));
+        assert!(!hgt.is_satisfied_by("+cm"));
+
+        let year = Constraint::Year {
+            min: 1920,
+            max: 2002,
+        };
+
+        assert!(year.is_satisfied_by("2002"));
+        assert!(!year.is_satisfied_by("2003"));
+        assert!(!year.is_satisfied_by("+200"));
+        // read like `u64::from_str`, as the original checks did
+        assert!(year.is_satisfied_by("02002"));
+        assert!(year.is_satisfied_by("+2000"));
+        assert!(!year.is_satisfied_by("2OO2"));
+
+        let pattern =
+            |p: &str, value: &str| Constraint::Pattern(p.parse().unwrap()).is_satisfied_by(value);
+
+        assert!(pattern("#[0-9a-f]{6}", "#123abc"));
+        assert!(!pattern("#[0-9a-f]{6}", "#123abz"));
+        assert!(!pattern("#[0-9a-f]{6}", "123abc"));
+        assert!(pattern("a+b?[^x-z]*", "aaac"));
+        assert!(pattern("a{2,}.\\.", "aaab."));
+        assert!(!pattern("a{2,3}", "aaaa"));
+        assert!(pattern("[a-]+", "-a-"));
+        assert!(pattern("a?b*a{0,2}b", "ab"));
+        assert!(pattern("", ""));
+        assert!(!pattern("", "a"));
+
+        // would take exponential time with backtracking
+        let many = "a*".repeat(30) + "b";
+        assert!(!pattern(&many, &"a".repeat(50)));
+        assert!(pattern(&many, &("a".repeat(50) + "b")));
+
+        assert!(pattern("\\(a\\|b\\)", "(a|b)"));
+
+        for invalid in [
+            "[a-z", "a{3,1}", "+a", "a{2", "[z-a]", "a\\", "(cm|in)", "a|b", "a)",
+        ] {
+            assert!(invalid.parse::<Pattern>().is_err(), "{invalid}");
+        }
+
+        assert_eq!(parse_number("0042"), Some(42));
+        assert_eq!(parse_number(&u64::MAX.to_string()), Some(u64::MAX));
+        assert_eq!(parse_number("18446744073709551616"), None);
+        assert_eq!(parse_number("+42"), Some(42));
+        assert_eq!(parse_number("+"), None);
+        assert_eq!(parse_number("-42"), None);
+        assert_eq!(parse_number(""), None);
+
+        assert!(Constraint::Digits(9).is_satisfied_by("000000001"));
+        assert!(!Constraint::Digits(9).is_satisfied_by("0123456789"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let p: Passport = "byr:1919 iyr:2010 hgt:190in hcl:123abc ecl:zzz pid:12345678a cid:1"
+            .parse()
+            .unwrap();
+        let errors: Vec<_> = Policy::standard()
+            .validate(&p)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            errors,
+            [
+                "byr \"1919\" not between 1920 and 2002",
+                "eyr missing",
+                "hgt \"190in\" out of range, expected 150-193cm or 59-76in",
+                "hcl \"123abc\" doesn't match #[0-9a-fA-F]*",
+                "ecl \"zzz\" not one of amb, blu, brn, gry, grn, hzl, oth",
+                "pid \"12345678a\" not a 9 digit number",
+            ]
+        );
+
+        let hgt = &Policy::standard().rules[3].constraint;
+
+        assert_eq!(
+            hgt.check("190").unwrap_err(),
+            "unknown unit \"\", expected 150-193cm or 59-76in"
+        );
+        assert_eq!(
+            hgt.check("in").unwrap_err(),
+            "not a number, expected 150-193cm or 59-76in"
+        );
+    }
+
+    #[test]
+    fn test_standard_policy() {
+        let standard = Policy::standard();
+
+        assert_eq!(standard.rules.len(), 8);
+        assert_eq!(
+            standard.rules[3],
+            FieldRule::required(
+                "hgt",
+                Constraint::Number(vec![
+                    UnitRange::new("cm", 150, 193),
+                    UnitRange::new("in", 59, 76),
+                ])
+            )
+        );
+        assert_eq!(
+            standard.rules[7],
+            FieldRule::optional("cid", Constraint::Any)
+        );
+        assert!(std::ptr::eq(standard, Policy::standard()));
+    }
+
+    #[cfg(feature = "policy-files")]
+    #[test]
+    fn test_policy_files() {
+        assert_eq!(
+            &Policy::from_toml(STANDARD_POLICY).unwrap(),
+            Policy::standard()
+        );
+
+        let json = r#"{"rules": [
+            {"field": "pid", "digits": 9},
+            {"field": "ecl", "required": false, "one_of": ["blu"]}
+        ]}"#;
+
+        assert_eq!(
+            Policy::from_json(json).unwrap(),
+            Policy {
+                rules: vec![
+                    FieldRule::required("pid", Constraint::Digits(9)),
+                    FieldRule::optional("ecl", Constraint::OneOf(vec!["blu".to_string()])),
+                ]
+            }
+        );
+
+        for invalid in [
+            "[[rules]]\nfield = \"pid\"\ndigits = 9\npattern = \"[0-9]+\"",
+            "[[rules]]\nfield = \"hcl\"\npattern = \"#[0-9\"",
+            "[[rules]]\nfield = \"pid\"\nlength = 9",
+            "[[rules]]\ndigits = 9",
+        ] {
+            assert!(Policy::from_toml(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = Policy {
+            rules: vec![
+                FieldRule::required("cid", Constraint::Digits(3)),
+                FieldRule::optional("ecl", Constraint::OneOf(vec!["blu".to_string()])),
+            ],
+        };
+
+        let passport = |s: &str| s.parse::<Passport>().unwrap();
+
+        assert!(policy.is_valid(&passport("cid:123")));
+        assert!(policy.is_valid(&passport("cid:123 ecl:blu byr:1")));
+        assert!(!policy.is_valid(&passport("cid:123 ecl:brn")));
+        assert!(!policy.is_valid(&passport("ecl:blu")));
+        assert_eq!(policy.presence_only().rules.len(), 1);
+    }
+}
@@ -0,0 +1,409 @@
+use std::{error::Error, fmt, ops::RangeInclusive, str::FromStr, sync::OnceLock};
+
+use parse_display::{Display, FromStr};
+
+use crate::{rules::parse_number, Constraint, FieldError, Passport, Policy};
+
+const CM_PER_INCH: f64 = 2.54;
+
+/// Ranges of the standard policy, so `ValidPassport` accepts the same
+/// passports as `Policy::standard`
+struct Ranges {
+    birth_years: RangeInclusive<u64>,
+    issue_years: RangeInclusive<u64>,
+    expiration_years: RangeInclusive<u64>,
+    heights_cm: RangeInclusive<u64>,
+    heights_in: RangeInclusive<u64>,
+}
+
+impl Ranges {
+    /// # Panics
+    ///
+    /// Panics if the standard policy lacks one of the ranges
+    fn standard() -> &'static Self {
+        static RANGES: OnceLock<Ranges> = OnceLock::new();
+
+        RANGES.get_or_init(|| {
+            let constraint = |field: &str| {
+                Policy::standard()
+                    .rules
+                    .iter()
+                    .find(|rule| rule.field == field)
+                    .map(|rule| &rule.constraint)
+            };
+            let years = |field| match constraint(field) {
+                Some(Constraint::Year { min, max }) => u64::from(*min)..=u64::from(*max),
+                _ => panic!("standard policy has no years for {field}"),
+            };
+            let heights = |unit| {
+                match constraint("hgt") {
+                    Some(Constraint::Number(ranges)) => ranges
+                        .iter()
+                        .find(|range| range.unit == unit)
+                        .map(|range| range.min..=range.max),
+                    _ => None,
+                }
+                .unwrap_or_else(|| panic!("standard policy has no heights in {unit}"))
+            };
+
+            Ranges {
+                birth_years: years("byr"),
+                issue_years: years("iyr"),
+                expiration_years: years("eyr"),
+                heights_cm: heights("cm"),
+                heights_in: heights("in"),
+            }
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Height {
+    Centimeters(u16),
+    Inches(u16),
+}
+
+impl Height {
+    #[must_use]
+    pub fn centimeters(self) -> f64 {
+        match self {
+            Height::Centimeters(cm) => f64::from(cm),
+            Height::Inches(inches) => f64::from(inches) * CM_PER_INCH,
+        }
+    }
+
+    #[must_use]
+    pub fn inches(self) -> f64 {
+        match self {
+            Height::Centimeters(cm) => f64::from(cm) / CM_PER_INCH,
+            Height::Inches(inches) => f64::from(inches),
+        }
+    }
+
+    fn is_plausible(self) -> bool {
+        let ranges = Ranges::standard();
+
+        match self {
+            Height::Centimeters(cm) => ranges.heights_cm.contains(&u64::from(cm)),
+            Height::Inches(inches) => ranges.heights_in.contains(&u64::from(inches)),
+        }
+    }
+}
+
+impl FromStr for Height {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            parse_number(n)
+                .and_then(|n| n.try_into().ok())
+                .ok_or_else(|| "not a number".to_string())
+        };
+
+        match (s.strip_suffix("cm"), s.strip_suffix("in")) {
+            (Some(cm), _) => number(cm).map(Height::Centimeters),
+            (_, Some(inches)) => number(inches).map(Height::Inches),
+            _ => Err("unknown unit, expected cm or in".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Height::Centimeters(cm) => write!(f, "{cm}cm"),
+            Height::Inches(inches) => write!(f, "{inches}in"),
+        }
+    }
+}
+
+/// `#` followed by hex digits of either case, as many as the passport has
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HairColor(String);
+
+impl HairColor {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Red, green and blue channels of a `#rrggbb` color, `None` for colors
+    /// with another number of digits
+    #[must_use]
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        let hex = &self.0[1..];
+
+        if hex.len() != 6 {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl FromStr for HairColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('#') {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => Ok(HairColor(s.to_string())),
+            _ => Err("not a # followed by hex digits".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for HairColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, FromStr, PartialEq, Eq)]
+#[display(style = "lowercase")]
+pub enum EyeColor {
+    Amb,
+    Blu,
+    Brn,
+    Gry,
+    Grn,
+    Hzl,
+    Oth,
+}
+
+/// Nine digits, leading zeros included
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PassportId(String);
+
+impl PassportId {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for PassportId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 9 && s.bytes().all(|b| b.is_ascii_digit()) {
+            Ok(PassportId(s.to_string()))
+        } else {
+            Err("not a 9 digit number".to_string())
+        }
+    }
+}
+
+impl fmt::Display for PassportId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Passport satisfying the standard policy, with every field parsed. The
+/// ranges are those of `Policy::standard`, custom policies don't apply.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidPassport {
+    pub birth_year: u16,
+    pub issue_year: u16,
+    pub expiration_year: u16,
+    pub height: Height,
+    pub hair_color: HairColor,
+    pub eye_color: EyeColor,
+    pub passport_id: PassportId,
+    pub country_id: Option<String>,
+}
+
+/// Every field error that kept a `Passport` from being a `ValidPassport`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidPassport {
+    pub errors: Vec<FieldError>,
+}
+
+impl fmt::Display for InvalidPassport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<_> = self.errors.iter().map(ToString::to_string).collect();
+
+        write!(f, "{}", errors.join("; "))
+    }
+}
+
+impl Error for InvalidPassport {}
+
+fn parse_year(s: &str, years: &RangeInclusive<u64>) -> Result<u16, String> {
+    let year = parse_number(s).ok_or_else(|| "not a year".to_string())?;
+
+    years
+        .contains(&year)
+        .then(|| u16::try_from(year).ok())
+        .flatten()
+        .ok_or_else(|| format!("not between {} and {}", years.start(), years.end()))
+}
+
+/// Parses field `key` of `p`, recording the error if it's missing or
+/// malformed
+fn field<T>(
+    p: &Passport,
+    key: &str,
+    errors: &mut Vec<FieldError>,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Option<T> {
+    let value = p.get(key);
+    let result = match value {
+        Some(value) => parse(value),
+        None => Err("missing".to_string()),
+    };
+
+    result
+        .map_err(|reason| {
+            errors.push(FieldError {
+                field: key.to_string(),
+                value: value.map(str::to_string),
+                reason,
+            });
+        })
+        .ok()
+}
+
+impl TryFrom<&Passport> for ValidPassport {
+    type Error = InvalidPassport;
+
+    fn try_from(p: &Passport) -> Result<Self, Self::Error> {
+        let ranges = Ranges::standard();
+        let mut errors = Vec::new();
+        let e = &mut errors;
+
+        let birth_year = field(p, "byr", e, |s| parse_year(s, &ranges.birth_years));
+        let issue_year = field(p, "iyr", e, |s| parse_year(s, &ranges.issue_years));
+        let expiration_year = field(p, "eyr", e, |s| parse_year(s, &ranges.expiration_years));
+        let height = field(p, "hgt", e, |s| {
+            let height: Height = s.parse()?;
+
+            if height.is_plausible() {
+                Ok(height)
+            } else {
+                Err("out of range".to_string())
+            }
+        });
+        let hair_color = field(p, "hcl", e, str::parse);
+        let eye_color = field(p, "ecl", e, |s| {
+            s.parse().map_err(|_| "not an eye color".to_string())
+        });
+        let passport_id = field(p, "pid", e, str::parse);
+
+        let (
+            Some(birth_year),
+            Some(issue_year),
+            Some(expiration_year),
+            Some(height),
+            Some(hair_color),
+            Some(eye_color),
+            Some(passport_id),
+        ) = (
+            birth_year,
+            issue_year,
+            expiration_year,
+            height,
+            hair_color,
+            eye_color,
+            passport_id,
+        )
+        else {
+            return Err(InvalidPassport { errors });
+        };
+
+        Ok(ValidPassport {
+            birth_year,
+            issue_year,
+            expiration_year,
+            height,
+            hair_color,
+            eye_color,
+            passport_id,
+            country_id: p.get("cid").map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_passport_valid, Problem};
+
+    #[test]
+    fn test_valid_passport() {
+        let p: Passport = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f"
+            .parse()
+            .unwrap();
+        let valid = ValidPassport::try_from(&p).unwrap();
+
+        assert_eq!(valid.birth_year, 1980);
+        assert_eq!(valid.height, Height::Inches(74));
+        assert!((valid.height.centimeters() - 187.96).abs() < 1e-9);
+        assert_eq!(valid.hair_color.rgb(), Some((0x62, 0x3a, 0x2f)));
+        assert_eq!(valid.hair_color.to_string(), "#623a2f");
+        assert_eq!("#12".parse::<HairColor>().unwrap().rgb(), None);
+        assert_eq!(valid.eye_color, EyeColor::Grn);
+        assert_eq!(valid.passport_id.as_str(), "087499704");
+        assert_eq!(valid.country_id, None);
+
+        let p: Passport = "pid:0874997 hgt:74 ecl:red iyr:2012 eyr:2030 hcl:623a2f"
+            .parse()
+            .unwrap();
+        let error = ValidPassport::try_from(&p).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "byr missing; hgt \"74\" unknown unit, expected cm or in; \
+             hcl \"623a2f\" not a # followed by hex digits; \
+             ecl \"red\" not an eye color; pid \"0874997\" not a 9 digit number"
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_standard_policy() {
+        let input = "\
+eyr:1972 cid:100 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019 hcl:#602927 eyr:1967 hgt:170cm ecl:grn pid:012533040 byr:1946
+
+hcl:#888785 hgt:164cm byr:2001 iyr:2015 cid:88 pid:545766238 ecl:hzl eyr:2022
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
+
+hgt:59cm ecl:zzz eyr:2038 hcl:74454a iyr:2023 pid:3556412378 byr:2007
+
+iyr:2010 hgt:77in hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
+        let mut p: Problem = input.parse().unwrap();
+        let valid = "iyr:2010 hcl:#b6652a ecl:blu eyr:2021 pid:093154719";
+
+        // both sides of every range boundary
+        for fields in [
+            "byr:1920 hgt:150cm",
+            "byr:1919 hgt:150cm",
+            "byr:2002 hgt:193cm",
+            "byr:2003 hgt:193cm",
+            "byr:1944 hgt:149cm",
+            "byr:1944 hgt:194cm",
+            "byr:1944 hgt:59in",
+            "byr:1944 hgt:58in",
+            "byr:1944 hgt:76in",
+            "byr:1944 hgt:77in",
+            "byr:1944 hgt:99999999999999999999cm",
+            "byr:02002 hgt:+170cm",
+            "byr:+1944 hgt:+cm",
+        ] {
+            p.passports
+                .push(format!("{valid} {fields}").parse().unwrap());
+        }
+
+        for passport in &p.passports {
+            assert_eq!(
+                ValidPassport::try_from(passport).is_ok(),
+                is_passport_valid(passport),
+                "{passport}"
+            );
+        }
+    }
+}
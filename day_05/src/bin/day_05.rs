@@ -1,10 +1,12 @@
 use std::{env, fs};
 
-use anyhow::{anyhow, Context};
+use anyhow::anyhow;
+use common::Config;
 use day_05::{find_my_seat_id, str_to_seat, Problem};
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let input_path = config.input_path(5, args.first().map(String::as_str));
     let content = fs::read_to_string(input_path)?;
     let Problem { boarding_passes } = content.parse()?;
 
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 #[must_use]
 pub fn str_to_seat(s: &str) -> u16 {
@@ -17,7 +17,8 @@ pub fn str_to_seat(s: &str) -> u16 {
     a
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     pub boarding_passes: Vec<String>,
 }
@@ -32,6 +33,16 @@ impl FromStr for Problem {
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for boarding_pass in &self.boarding_passes {
+            writeln!(f, "{boarding_pass}")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// # Errors
 ///
 /// Returns error when input slice length is greater than `u16::MAX`
@@ -77,4 +88,13 @@ mod tests {
     fn test_find_my_seat_id() {
         assert_eq!(find_my_seat_id(&[9, 4, 8, 5, 6]).unwrap(), 7);
     }
+
+    #[test]
+    fn test_display_round_trip() {
+        let input = "BFFFBBFRRR\nFFFBBBFRRR\nBBFFBBFRLL\n";
+        let p: Problem = input.parse().unwrap();
+
+        assert_eq!(p.to_string(), input);
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
 }
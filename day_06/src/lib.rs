@@ -1,6 +1,7 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{collections::HashSet, fmt, str::FromStr};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     groups: Vec<String>,
 }
@@ -15,6 +16,12 @@ impl FromStr for Problem {
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.groups.join("\n\n"))
+    }
+}
+
 fn count_unique(group: &str) -> usize {
     let group_unique_answers = group.chars().filter(|c| *c != '\n').collect::<HashSet<_>>();
 
@@ -83,4 +90,12 @@ b";
 
         assert_eq!(sum_shared_answers(&p), 6);
     }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), TEST_INPUT);
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
 }
@@ -1,10 +1,11 @@
 use std::{env, fs};
 
-use anyhow::Context;
+use common::Config;
 use day_07::{count_contained_bags, count_containing_bags, Problem};
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let input_path = config.input_path(7, args.first().map(String::as_str));
     let content = fs::read_to_string(input_path)?;
     let p: Problem = content.as_str().try_into()?;
 
@@ -1,11 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 use anyhow::{anyhow, Context};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     name_to_idx: HashMap<&'a str, usize>,
-    #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(borrow))]
     idx_to_name: Vec<&'a str>,
     contains: Vec<Vec<(usize, usize)>>,
     contained_by: Vec<Vec<(usize, usize)>>,
@@ -103,6 +108,58 @@ impl<'a> TryFrom<&'a str> for Problem<'a> {
     }
 }
 
+impl fmt::Display for Problem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (bag_idx, bag_name) in self.idx_to_name.iter().enumerate() {
+            write!(f, "{bag_name} bags contain ")?;
+
+            if self.contains[bag_idx].is_empty() {
+                writeln!(f, "no other bags.")?;
+                continue;
+            }
+
+            for (i, &(contained_bag_idx, count)) in self.contains[bag_idx].iter().enumerate() {
+                let separator = if i == 0 { "" } else { ", " };
+                let plural = if count == 1 { "" } else { "s" };
+                let contained_bag_name = self.idx_to_name[contained_bag_idx];
+
+                write!(f, "{separator}{count} {contained_bag_name} bag{plural}")?;
+            }
+
+            writeln!(f, ".")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Problem<'a> {
+    /// Bags directly contained in bag `bag_idx` as (name, count), sorted by
+    /// name
+    fn named_contents(&self, bag_idx: usize) -> Vec<(&'a str, usize)> {
+        let mut contents = self.contains[bag_idx]
+            .iter()
+            .map(|&(idx, count)| (self.idx_to_name[idx], count))
+            .collect::<Vec<_>>();
+        contents.sort_unstable();
+
+        contents
+    }
+}
+
+impl PartialEq for Problem<'_> {
+    /// Bag indices depend on the order in which rules were listed, so bags
+    /// are compared by name
+    fn eq(&self, other: &Self) -> bool {
+        self.name_to_idx.len() == other.name_to_idx.len()
+            && self.name_to_idx.iter().all(|(name, &idx)| {
+                other.name_to_idx.get(name).is_some_and(|&other_idx| {
+                    self.named_contents(idx) == other.named_contents(other_idx)
+                })
+            })
+    }
+}
+
 /// # Errors
 ///
 /// Returns error if bag with `bag_name` does not exist
@@ -212,4 +269,24 @@ dark violet bags contain no other bags.";
         let p: Problem = TEST_INPUT_NESTED.try_into().unwrap();
         assert_eq!(count_contained_bags(&p, "shiny gold").unwrap(), 126);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let p: Problem = TEST_INPUT.try_into().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+        let p: Problem = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(count_containing_bags(&p, "shiny gold").unwrap(), 4);
+        assert_eq!(count_contained_bags(&p, "shiny gold").unwrap(), 32);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.try_into().unwrap();
+        let s = p.to_string();
+
+        assert!(s.starts_with("light red bags contain 1 bright white bag, 2 muted yellow bags.\n"));
+        assert_eq!(Problem::try_from(s.as_str()).unwrap(), p);
+    }
 }
@@ -1,8 +1,9 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use anyhow::{anyhow, bail};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Instruction {
     Acc(i32),
     Jmp(i32),
@@ -28,7 +29,18 @@ impl FromStr for Instruction {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Acc(v) => write!(f, "acc {v:+}"),
+            Instruction::Jmp(v) => write!(f, "jmp {v:+}"),
+            Instruction::Nop(v) => write!(f, "nop {v:+}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     instructions: Vec<Instruction>,
 }
@@ -46,6 +58,16 @@ impl FromStr for Problem {
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for instruction in &self.instructions {
+            writeln!(f, "{instruction}")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// # Errors
 ///
 /// Returns error if instruction pointer `i` runs out of (positive) `i32` range
@@ -160,4 +182,25 @@ acc +6";
         let p: Problem = TEST_INPUT.parse().unwrap();
         assert_eq!(brute_force(&p).unwrap(), 8);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+
+        assert!(json.starts_with(r#"{"instructions":[{"Nop":0},{"Acc":1},{"Jmp":4}"#));
+
+        let p: Problem = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(run_until_first_loop(&p).unwrap(), (5, false));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
 }
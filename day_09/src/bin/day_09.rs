@@ -1,20 +1,26 @@
 use std::{env, fs};
 
-use anyhow::Context;
-use day_09::{find_continuous_set_summing_to_value, find_first_not_following_the_rule, Problem};
+use common::{Config, Integer};
+use day_09::{
+    find_continuous_set_summing_to_value, find_first_not_following_the_rule, Problem,
+    PREAMBLE_LENGTH,
+};
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let input_path = config.input_path(9, args.first().map(String::as_str));
     let content = fs::read_to_string(input_path)?;
-    let Problem { numbers } = content.parse()?;
+    let Problem::<i64> { numbers } = content.parse()?;
 
-    let value = find_first_not_following_the_rule(25, &numbers);
+    let preamble_length = config.param_or(9, "preamble_length", PREAMBLE_LENGTH)?;
+
+    let value = find_first_not_following_the_rule(preamble_length, &numbers)?;
     println!("Part 1: {value}");
 
-    let v = find_continuous_set_summing_to_value(value, &numbers);
+    let v = find_continuous_set_summing_to_value(value, &numbers)?;
     let min = v.iter().min().unwrap();
     let max = v.iter().max().unwrap();
-    println!("Part 2: {}", min + max);
+    println!("Part 2: {}", min.try_add(*max)?);
 
     Ok(())
 }
@@ -1,14 +1,23 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
+    fmt,
     str::FromStr,
 };
 
-#[derive(Debug)]
-pub struct Problem {
-    pub numbers: Vec<i64>,
+use anyhow::bail;
+use common::{Integer, OverflowError};
+
+/// Overridable with `day_09.preamble_length` config parameter
+pub const PREAMBLE_LENGTH: usize = 25;
+
+/// Numbers are `i64` by default, any `Integer` (e.g. `i128` or `u64`) works
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Problem<T = i64> {
+    pub numbers: Vec<T>,
 }
 
-impl FromStr for Problem {
+impl<T: Integer> FromStr for Problem<T> {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -18,16 +27,41 @@ impl FromStr for Problem {
     }
 }
 
-#[must_use]
-pub fn find_first_not_following_the_rule(preamble_length: usize, numbers: &[i64]) -> i64 {
+impl<T: fmt::Display> fmt::Display for Problem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for number in &self.numbers {
+            writeln!(f, "{number}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// # Errors
+///
+/// Returns error if the preamble is empty or doesn't leave any numbers to
+/// check or if every number follows the rule. Sums of two numbers
+/// overflowing `T` can't match a number and are skipped.
+pub fn find_first_not_following_the_rule<T: Integer>(
+    preamble_length: usize,
+    numbers: &[T],
+) -> Result<T, anyhow::Error> {
+    if preamble_length == 0 || preamble_length >= numbers.len() {
+        bail!(
+            "preamble length has to be between 1 and {}, got {preamble_length}",
+            numbers.len().saturating_sub(1)
+        );
+    }
+
     // keep track of sums of every pair in `preamble_length` window of `numbers`
-    let mut sum_counts: HashMap<i64, usize> = HashMap::new();
+    let mut sum_counts: HashMap<T, usize> = HashMap::new();
 
     // pre-populate `sum_counts` with numbers from the preamble
     for i in 0..preamble_length {
         for j in (i + 1)..preamble_length {
-            let sum = numbers[i] + numbers[j];
-            sum_counts.entry(sum).and_modify(|v| *v += 1).or_insert(1);
+            if let Some(sum) = numbers[i].checked_add(numbers[j]) {
+                sum_counts.entry(sum).and_modify(|v| *v += 1).or_insert(1);
+            }
         }
     }
 
@@ -35,7 +69,7 @@ pub fn find_first_not_following_the_rule(preamble_length: usize, numbers: &[i64]
         let new_arrival = window[preamble_length];
 
         if !sum_counts.contains_key(&new_arrival) {
-            return new_arrival;
+            return Ok(new_arrival);
         }
 
         let staying_in_window = &window[1..preamble_length];
@@ -43,50 +77,59 @@ pub fn find_first_not_following_the_rule(preamble_length: usize, numbers: &[i64]
 
         // update `sum_counts` taking into account two values that are entering
         // and leaving the window
-        for v in staying_in_window {
-            let leaving_sum = v + leaving_value;
-            let joining_sum = v + new_arrival;
-
-            match sum_counts.entry(leaving_sum) {
-                Entry::Occupied(mut o) => {
-                    let should_be_removed = *o.get() == 1;
-
-                    if should_be_removed {
-                        o.remove();
-                    } else {
-                        *o.get_mut() -= 1;
+        for &v in staying_in_window {
+            // an overflowing sum was never counted, so there is nothing to remove
+            if let Some(leaving_sum) = v.checked_add(leaving_value) {
+                match sum_counts.entry(leaving_sum) {
+                    Entry::Occupied(mut o) => {
+                        let should_be_removed = *o.get() == 1;
+
+                        if should_be_removed {
+                            o.remove();
+                        } else {
+                            *o.get_mut() -= 1;
+                        }
                     }
+                    Entry::Vacant(_) => unreachable!(),
                 }
-                Entry::Vacant(_) => unreachable!(),
             }
 
-            sum_counts
-                .entry(joining_sum)
-                .and_modify(|v| *v += 1)
-                .or_insert(1);
+            if let Some(joining_sum) = v.checked_add(new_arrival) {
+                sum_counts
+                    .entry(joining_sum)
+                    .and_modify(|v| *v += 1)
+                    .or_insert(1);
+            }
         }
     }
 
-    unreachable!("no solution found")
+    bail!("every number is a sum of two of the {preamble_length} before it")
 }
 
-#[must_use]
-pub fn find_continuous_set_summing_to_value(target: i64, numbers: &[i64]) -> &[i64] {
+/// # Errors
+///
+/// Returns error if a sum of the numbers overflows `T`
+pub fn find_continuous_set_summing_to_value<T: Integer>(
+    target: T,
+    numbers: &[T],
+) -> Result<&[T], OverflowError> {
     let mut i = 0;
     let mut j = 1;
     let mut sum = numbers[i];
 
     while sum != target {
-        if sum + numbers[j] <= target {
-            sum += numbers[j];
+        let extended = sum.try_add(numbers[j])?;
+
+        if extended <= target {
+            sum = extended;
             j += 1;
         } else {
-            sum -= numbers[i];
+            sum = sum.try_sub(numbers[i])?;
             i += 1;
         }
     }
 
-    &numbers[i..j]
+    Ok(&numbers[i..j])
 }
 
 #[cfg(test)]
@@ -117,16 +160,43 @@ mod tests {
 
     #[test]
     fn test_find_first_not_following_the_rule() {
-        let Problem { numbers } = TEST_INPUT.parse().unwrap();
-        assert_eq!(find_first_not_following_the_rule(5, &numbers), 127);
+        let Problem { numbers } = TEST_INPUT.parse::<Problem>().unwrap();
+        assert_eq!(find_first_not_following_the_rule(5, &numbers).unwrap(), 127);
+
+        assert!(find_first_not_following_the_rule(0, &numbers).is_err());
+        assert!(find_first_not_following_the_rule(20, &numbers).is_err());
+        assert!(find_first_not_following_the_rule(5000, &numbers).is_err());
+        assert!(find_first_not_following_the_rule(2, &[1, 2, 3, 5]).is_err());
     }
 
     #[test]
     fn test_find_continuous_set_summing_to_value() {
-        let Problem { numbers } = TEST_INPUT.parse().unwrap();
+        let Problem { numbers } = TEST_INPUT.parse::<Problem>().unwrap();
         assert_eq!(
-            find_continuous_set_summing_to_value(127, &numbers),
+            find_continuous_set_summing_to_value(127, &numbers).unwrap(),
             vec![15, 25, 47, 40]
         );
     }
+
+    #[test]
+    fn test_generic_integers() {
+        // overflowing sums are skipped, they can't match any number
+        let numbers: [u8; 4] = [100, 200, 50, 60];
+        assert_eq!(find_first_not_following_the_rule(2, &numbers).unwrap(), 50);
+        assert_eq!(
+            find_first_not_following_the_rule(2, &[u64::MAX, 1, 2, 3]).unwrap(),
+            2
+        );
+
+        let Problem { numbers } = TEST_INPUT.parse::<Problem<u64>>().unwrap();
+        assert_eq!(find_first_not_following_the_rule(5, &numbers).unwrap(), 127);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
 }
@@ -1,6 +1,7 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     numbers: Vec<u64>,
 }
@@ -15,6 +16,16 @@ impl FromStr for Problem {
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for number in &self.numbers {
+            writeln!(f, "{number}")?;
+        }
+
+        Ok(())
+    }
+}
+
 fn count_differences(numbers: &[u64]) -> (u64, u64) {
     // assumes `numbers` is sorted
 
@@ -165,4 +176,12 @@ mod tests {
         assert_eq!(solve_part_2(&p1), 8);
         assert_eq!(solve_part_2(&p2), 19208);
     }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
 }
@@ -1,17 +1,28 @@
 use std::{env, fs};
 
-use anyhow::Context;
-use day_11::{solve_part_1, solve_part_2, Problem};
+use common::Config;
+use day_11::{
+    solve_part_1_with_limit, solve_part_2_with_limit, Problem, OCCUPIED_SEATS_LIMIT,
+    VISIBLE_OCCUPIED_SEATS_LIMIT,
+};
 
 fn main() -> Result<(), anyhow::Error> {
-    let input_path = env::args().nth(1).context("missing path argument")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+    let input_path = config.input_path(11, args.first().map(String::as_str));
     let content = fs::read_to_string(input_path)?;
     let p: Problem = content.parse()?;
 
-    let occupied_seats = solve_part_1(p.clone());
+    let limit = config.param_or(11, "occupied_seats_limit", OCCUPIED_SEATS_LIMIT)?;
+    let visible_limit = config.param_or(
+        11,
+        "visible_occupied_seats_limit",
+        VISIBLE_OCCUPIED_SEATS_LIMIT,
+    )?;
+
+    let occupied_seats = solve_part_1_with_limit(p.clone(), limit)?;
     println!("Part 1: {occupied_seats}");
 
-    let occupied_seats = solve_part_2(p);
+    let occupied_seats = solve_part_2_with_limit(p, visible_limit)?;
     println!("Part 2: {occupied_seats}");
 
     Ok(())
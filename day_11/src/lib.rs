@@ -1,16 +1,36 @@
-#![allow(clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::match_on_vec_items)]
-use std::{collections::HashMap, str::FromStr};
+// match_on_vec_items is gone from newer clippy, keep the allow for the pinned toolchain
+#![allow(renamed_and_removed_lints)]
+#![allow(
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss,
+    clippy::match_on_vec_items
+)]
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
 
 use anyhow::bail;
 
-#[derive(Clone, Debug, PartialEq)]
+/// Occupied adjacent seats that make a person leave their seat, overridable
+/// with `day_11.occupied_seats_limit` config parameter
+pub const OCCUPIED_SEATS_LIMIT: usize = 4;
+
+/// Occupied visible seats that make a person leave their seat, overridable
+/// with `day_11.visible_occupied_seats_limit` config parameter
+pub const VISIBLE_OCCUPIED_SEATS_LIMIT: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Space {
     Floor,
     Empty,
     Occupied,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     map: Vec<Vec<Space>>,
 }
@@ -37,6 +57,24 @@ impl FromStr for Problem {
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.map {
+            let row: String = row
+                .iter()
+                .map(|space| match space {
+                    Space::Floor => '.',
+                    Space::Empty => 'L',
+                    Space::Occupied => '#',
+                })
+                .collect();
+            writeln!(f, "{row}")?;
+        }
+
+        Ok(())
+    }
+}
+
 fn neighbour_offsets(i: usize, j: usize, max_i: usize, max_j: usize) -> &'static [(isize, isize)] {
     match (i, j) {
         (0, 0) => &[(0, 1), (1, 0), (1, 1)],
@@ -155,48 +193,96 @@ fn make_visible_seats_map(input: &[Vec<Space>]) -> HashMap<(usize, usize), Vec<(
     map
 }
 
+/// # Panics
+///
+/// Panics if the seats don't settle, which the puzzle limit rules out
 #[must_use]
 pub fn solve_part_1(p: Problem) -> usize {
+    solve_part_1_with_limit(p, OCCUPIED_SEATS_LIMIT).expect("seats settle with the puzzle limit")
+}
+
+/// # Errors
+///
+/// Returns error if the seats go back to an earlier layout instead of
+/// settling, e.g. with a limit of 1
+pub fn solve_part_1_with_limit(
+    p: Problem,
+    occupied_seats_limit: usize,
+) -> Result<usize, anyhow::Error> {
     let Problem { mut map } = p;
+    let mut seen = HashSet::from([map.clone()]);
 
     loop {
-        let (output, changed) = run(&map, 4, |i, j, max_i, max_j| {
+        let (output, changed) = run(&map, occupied_seats_limit, |i, j, max_i, max_j| {
             neighbour_offsets(i, j, max_i, max_j)
                 .iter()
                 .map(|&(dx, dy)| ((i as isize + dx) as usize, (j as isize + dy) as usize))
                 .filter(|&(x, y)| map[x][y] == Space::Occupied)
                 .count()
         });
-        map = output;
 
         if !changed {
             break;
         }
+
+        check_not_seen(&mut seen, &output, occupied_seats_limit)?;
+        map = output;
     }
 
-    count_occupied_seats(&map)
+    Ok(count_occupied_seats(&map))
 }
 
+/// # Panics
+///
+/// Panics if the seats don't settle, which the puzzle limit rules out
 #[must_use]
 pub fn solve_part_2(p: Problem) -> usize {
+    solve_part_2_with_limit(p, VISIBLE_OCCUPIED_SEATS_LIMIT)
+        .expect("seats settle with the puzzle limit")
+}
+
+/// # Errors
+///
+/// Returns error if the seats go back to an earlier layout instead of
+/// settling
+pub fn solve_part_2_with_limit(
+    p: Problem,
+    occupied_seats_limit: usize,
+) -> Result<usize, anyhow::Error> {
     let Problem { mut map } = p;
     let visible_seats = make_visible_seats_map(&map);
+    let mut seen = HashSet::from([map.clone()]);
 
     loop {
-        let (output, changed) = run(&map, 5, |i, j, _, _| {
+        let (output, changed) = run(&map, occupied_seats_limit, |i, j, _, _| {
             visible_seats[&(i, j)]
                 .iter()
                 .filter(|(ni, nj)| map[*ni][*nj] == Space::Occupied)
                 .count()
         });
-        map = output;
 
         if !changed {
             break;
         }
+
+        check_not_seen(&mut seen, &output, occupied_seats_limit)?;
+        map = output;
+    }
+
+    Ok(count_occupied_seats(&map))
+}
+
+/// Remembers `map`, a layout seen before means the seats cycle forever
+fn check_not_seen(
+    seen: &mut HashSet<Vec<Vec<Space>>>,
+    map: &[Vec<Space>],
+    occupied_seats_limit: usize,
+) -> Result<(), anyhow::Error> {
+    if !seen.insert(map.to_vec()) {
+        bail!("seats never settle with a limit of {occupied_seats_limit}");
     }
 
-    count_occupied_seats(&map)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -244,7 +330,9 @@ L.LLLLL.LL";
     fn test_solve_part_1() {
         let p: Problem = TEST_INPUT.parse().unwrap();
 
-        assert_eq!(solve_part_1(p), 37);
+        assert_eq!(solve_part_1(p.clone()), 37);
+        assert!(solve_part_1_with_limit(p.clone(), 0).is_err());
+        assert!(solve_part_1_with_limit(p, 1).is_err());
     }
 
     #[test]
@@ -275,6 +363,15 @@ L.LLLLL.LL";
     fn test_solve_part_2() {
         let p: Problem = TEST_INPUT.parse().unwrap();
 
-        assert_eq!(solve_part_2(p), 26);
+        assert_eq!(solve_part_2(p.clone()), 26);
+        assert!(solve_part_2_with_limit(p, 0).is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
     }
 }
@@ -1,8 +1,9 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use parse_display::{Display, FromStr};
 
 #[derive(Debug, Display, FromStr, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Move {
     #[display("N{0}")]
     North(i32),
@@ -20,7 +21,8 @@ enum Move {
     Forward(i32),
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     moves: Vec<Move>,
 }
@@ -35,6 +37,16 @@ impl FromStr for Problem {
     }
 }
 
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.moves {
+            writeln!(f, "{m}")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[must_use]
 pub fn solve_part_1(p: &Problem) -> i32 {
     let Problem { moves } = p;
@@ -142,4 +154,28 @@ F11";
 
         assert_eq!(solve_part_2(&p), 286);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"moves":[{"Forward":10},{"North":3},{"Forward":7},{"RightTurn":90},{"Forward":11}]}"#
+        );
+
+        let p: Problem = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(solve_part_1(&p), 25);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let p: Problem = TEST_INPUT.parse().unwrap();
+
+        assert_eq!(p.to_string(), format!("{TEST_INPUT}\n"));
+        assert_eq!(p.to_string().parse::<Problem>().unwrap(), p);
+    }
 }
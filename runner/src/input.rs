@@ -0,0 +1,81 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context};
+use aoc::Config;
+
+const INPUT_URL: &str = "https://adventofcode.com";
+
+/// Path to the input of `day`. An explicit `arg` or the configured input
+/// directory are used as given, relative to the current directory. Without
+/// them, the input is taken from the cache directory, downloaded with the
+/// session token if it's not cached yet, and otherwise from `day_XX/input.txt`
+/// in the workspace.
+///
+/// # Errors
+///
+/// Returns error if a missing input can't be downloaded into the cache
+pub fn path(
+    root: &Path,
+    config: &Config,
+    day: u8,
+    arg: Option<&str>,
+) -> Result<PathBuf, anyhow::Error> {
+    if arg.is_some() || config.input_dir.is_some() {
+        return Ok(config.input_path(day, arg));
+    }
+
+    let Some(cache_dir) = &config.cache_dir else {
+        return Ok(root.join(config.input_path(day, None)));
+    };
+
+    let path = cache_dir
+        .join(config.year().to_string())
+        .join(format!("day_{day:02}.txt"));
+
+    if !path.is_file() {
+        download(config, day, &path)?;
+    }
+
+    Ok(path)
+}
+
+fn download(config: &Config, day: u8, path: &Path) -> Result<(), anyhow::Error> {
+    let session = config.session.as_deref().with_context(|| {
+        format!(
+            "{} is not cached and there's no session to download it with",
+            path.display()
+        )
+    })?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let url = format!("{INPUT_URL}/{}/day/{day}/input", config.year());
+    // the cookie header goes through stdin so the session doesn't show up in
+    // the process list
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--fail", "--header", "@-", "--output"])
+        .arg(path)
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+
+    if let Some(mut stdin) = curl.stdin.take() {
+        writeln!(stdin, "Cookie: session={session}")?;
+    }
+
+    let status = curl.wait()?;
+
+    if !status.success() {
+        bail!("failed to download {url}: {status}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::{env, fs, path::Path};
+
+use anyhow::{bail, Context};
+use aoc::Config;
+
+mod input;
+mod report;
+
+const USAGE: &str = "\
+usage: runner [flags] run <day> [input path]
+       runner [flags] report
+
+flags: --year <year> --input-dir <dir> --cache-dir <dir> --session <token>
+       --set day_XX.<param>=<value>";
+
+fn run(root: &Path, config: &Config, args: &[String]) -> Result<(), anyhow::Error> {
+    let day = args
+        .first()
+        .context("missing day argument")?
+        .parse::<u8>()?;
+
+    let input_path = input::path(root, config, day, args.get(1).map(String::as_str))?;
+    let content = fs::read_to_string(input_path)?;
+
+    for (part, answer) in (1..).zip(aoc::solve_both(day, &content, config)?) {
+        println!("Part {part}: {answer}");
+    }
+
+    Ok(())
+}
+
+fn update_report(root: &Path, config: &Config) -> Result<(), anyhow::Error> {
+    let reports = aoc::DAYS
+        .iter()
+        .map(|&day| report::collect(root, config, day))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let readme_path = root.join("README.md");
+    let readme = fs::read_to_string(&readme_path)?;
+    let readme = report::replace_section(&readme, &report::render(&reports))?;
+    fs::write(&readme_path, readme)?;
+
+    println!("Updated report for {} days", reports.len());
+
+    Ok(())
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .context("runner is expected to live in the workspace")?;
+    let (config, args) = Config::load(env::args().skip(1))?;
+
+    match args.first().map(String::as_str) {
+        Some("run") => run(root, &config, &args[1..]),
+        Some("report") => update_report(root, &config),
+        _ => bail!(USAGE),
+    }
+}
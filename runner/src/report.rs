@@ -0,0 +1,308 @@
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use aoc::Config;
+
+/// Answers to both parts of a day, `None` for parts that are not solved yet
+type Answers = [Option<String>; 2];
+
+pub const SECTION_START: &str = "<!-- report:start -->";
+pub const SECTION_END: &str = "<!-- report:end -->";
+
+#[derive(Debug, PartialEq)]
+pub enum Verification {
+    /// Every part matches its stored answer
+    Verified,
+    /// At least one part differs from or lacks its stored answer
+    Mismatch,
+    /// There is no `answers.txt` to check against
+    NoAnswers,
+    /// There is no `input.txt` to run the solver on
+    NoInput,
+}
+
+#[derive(Debug)]
+pub struct DayReport {
+    pub number: u8,
+    /// Whether each part gives an answer, both `false` without an input
+    pub solved: [bool; 2],
+    pub verification: Verification,
+    /// Median runtime in seconds, as exported by `hyperfine --export-json`
+    pub median_runtime: Option<f64>,
+    pub lines_of_code: usize,
+}
+
+#[must_use]
+pub fn crate_name(day: u8) -> String {
+    format!("day_{day:02}")
+}
+
+/// # Errors
+///
+/// Returns error when day's files can't be read or its input can't be downloaded
+pub fn collect(root: &Path, config: &Config, day: u8) -> Result<DayReport, anyhow::Error> {
+    let crate_dir = root.join(crate_name(day));
+    let input_path = crate::input::path(root, config, day, None)?;
+
+    let (solved, verification) = if input_path.exists() {
+        let input = fs::read_to_string(&input_path)?;
+        // a failing part is reported as unsolved instead of failing the report
+        let answers: Answers = [1, 2].map(|part| {
+            aoc::solve_with_config(day, part, &input, config)
+                .map_err(|e| eprintln!("warning: day {day} part {part}: {e:#}"))
+                .ok()
+                .map(|answer| answer.to_string())
+        });
+
+        let verification = match read_answers(&crate_dir.join("answers.txt"))? {
+            Some(expected) if answers_match(&answers, &expected) => Verification::Verified,
+            Some(_) => Verification::Mismatch,
+            None => Verification::NoAnswers,
+        };
+
+        ([answers[0].is_some(), answers[1].is_some()], verification)
+    } else {
+        ([false, false], Verification::NoInput)
+    };
+
+    Ok(DayReport {
+        number: day,
+        solved,
+        verification,
+        median_runtime: read_median_runtime(&bench_path(root, day))?,
+        lines_of_code: count_lines_of_code(&crate_dir.join("src"))?,
+    })
+}
+
+#[must_use]
+pub fn bench_path(root: &Path, day: u8) -> PathBuf {
+    root.join("bench").join(format!("{}.json", crate_name(day)))
+}
+
+/// Unsolved parts and parts without a stored answer don't match
+fn answers_match(answers: &Answers, expected: &Answers) -> bool {
+    answers
+        .iter()
+        .zip(expected)
+        .all(|(a, e)| a.is_some() && a == e)
+}
+
+/// Parses answers stored in the same `Part N: answer` format the binaries
+/// print, returns `None` if the file does not exist
+fn read_answers(path: &Path) -> Result<Option<Answers>, anyhow::Error> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    parse_answers(&content).map(Some)
+}
+
+fn parse_answers(s: &str) -> Result<Answers, anyhow::Error> {
+    let mut answers: Answers = [None, None];
+
+    for line in s.lines().filter(|l| !l.trim().is_empty()) {
+        let (part, answer) = match line.split_once(": ") {
+            Some(("Part 1", answer)) => (0, answer),
+            Some(("Part 2", answer)) => (1, answer),
+            _ => bail!("malformed answer line {}", line),
+        };
+
+        answers[part] = Some(answer.trim().to_string());
+    }
+
+    Ok(answers)
+}
+
+fn read_median_runtime(path: &Path) -> Result<Option<f64>, anyhow::Error> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let export: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("malformed benchmark export {}", path.display()))?;
+
+    Ok(export["results"][0]["median"].as_f64())
+}
+
+/// Counts non-blank lines of every `.rs` file under `dir`
+fn count_lines_of_code(dir: &Path) -> Result<usize, anyhow::Error> {
+    let mut count = 0;
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            count += count_lines_of_code(&path)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            count += fs::read_to_string(&path)?
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .count();
+        }
+    }
+
+    Ok(count)
+}
+
+fn format_runtime(seconds: f64) -> String {
+    if seconds < 1.0 {
+        format!("{:.1} ms", seconds * 1000.0)
+    } else {
+        format!("{seconds:.2} s")
+    }
+}
+
+#[must_use]
+pub fn render(reports: &[DayReport]) -> String {
+    let mut out = String::new();
+
+    out.push_str("| Day | Part 1 | Part 2 | Answers | Median runtime | Lines of code |\n");
+    out.push_str("| --- | :----: | :----: | ------- | -------------: | ------------: |\n");
+
+    for r in reports {
+        let part = |solved| match (&r.verification, solved) {
+            (Verification::NoInput, _) => "-",
+            (_, true) => "solved",
+            (_, false) => "unsolved",
+        };
+        let verification = match r.verification {
+            Verification::Verified => "verified",
+            Verification::Mismatch => "**mismatch**",
+            Verification::NoAnswers => "not stored",
+            Verification::NoInput => "no input",
+        };
+        let runtime = r
+            .median_runtime
+            .map_or_else(|| "-".to_string(), format_runtime);
+
+        let _ = writeln!(
+            out,
+            "| [{:02}](day_{:02}) | {} | {} | {} | {} | {} |",
+            r.number,
+            r.number,
+            part(r.solved[0]),
+            part(r.solved[1]),
+            verification,
+            runtime,
+            r.lines_of_code,
+        );
+    }
+
+    out
+}
+
+/// Replaces contents between `SECTION_START` and `SECTION_END` markers with
+/// `section`, appends the markers if `readme` does not have them yet
+///
+/// # Errors
+///
+/// Returns error when markers are present but out of order
+pub fn replace_section(readme: &str, section: &str) -> Result<String, anyhow::Error> {
+    match (readme.find(SECTION_START), readme.find(SECTION_END)) {
+        (Some(start), Some(end)) if start < end => Ok(format!(
+            "{}{SECTION_START}\n{section}{}",
+            &readme[..start],
+            &readme[end..]
+        )),
+        (None, None) => Ok(format!(
+            "{}\n\n{SECTION_START}\n{section}{SECTION_END}\n",
+            readme.trim_end()
+        )),
+        _ => bail!("malformed report section markers"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("Part 1: 514579\nPart 2: 241861950\n").unwrap();
+
+        assert_eq!(
+            answers,
+            [Some("514579".to_string()), Some("241861950".to_string())]
+        );
+        assert!(parse_answers("Part 3: 1").is_err());
+    }
+
+    #[test]
+    fn test_answers_match() {
+        let answers = [Some("514579".to_string()), Some("241861950".to_string())];
+
+        assert!(answers_match(
+            &answers,
+            &parse_answers("Part 1: 514579\nPart 2: 241861950").unwrap()
+        ));
+        assert!(!answers_match(
+            &answers,
+            &parse_answers("Part 1: 514579\nPart 2: 1").unwrap()
+        ));
+        assert!(!answers_match(
+            &answers,
+            &parse_answers("Part 1: 514579").unwrap()
+        ));
+        assert!(!answers_match(
+            &[Some("514579".to_string()), None],
+            &parse_answers("Part 1: 514579").unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_render() {
+        let report = |number, solved, verification| DayReport {
+            number,
+            solved,
+            verification,
+            median_runtime: None,
+            lines_of_code: 10,
+        };
+        let rendered = render(&[
+            report(1, [true, false], Verification::Mismatch),
+            report(2, [false, false], Verification::NoInput),
+        ]);
+        let rows: Vec<_> = rendered.lines().skip(2).collect();
+
+        assert_eq!(
+            rows,
+            [
+                "| [01](day_01) | solved | unsolved | **mismatch** | - | 10 |",
+                "| [02](day_02) | - | - | no input | - | 10 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let readme = "# Title\n";
+        let first = replace_section(readme, "a\n").unwrap();
+
+        assert_eq!(
+            first,
+            "# Title\n\n<!-- report:start -->\na\n<!-- report:end -->\n"
+        );
+
+        let second = replace_section(&first, "b\n").unwrap();
+
+        assert_eq!(
+            second,
+            "# Title\n\n<!-- report:start -->\nb\n<!-- report:end -->\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_section_malformed_markers() {
+        let readme = "<!-- report:end -->\n<!-- report:start -->\n";
+
+        assert!(replace_section(readme, "a\n").is_err());
+    }
+}